@@ -1,22 +1,26 @@
 #[path = "src/args.rs"]
 mod args;
+#[allow(dead_code)]
 #[path = "src/errors.rs"]
 mod errors;
+#[allow(dead_code)]
 #[path = "src/line.rs"]
 mod line;
+#[allow(dead_code)]
 #[path = "src/node.rs"]
 mod node;
+#[allow(dead_code)]
 #[path = "src/nodes.rs"]
 mod nodes;
+#[allow(dead_code)]
 #[path = "src/property_file_reader.rs"]
 mod property_file_reader;
 
 use clap::CommandFactory;
 
 fn main() -> std::io::Result<()> {
-    let out_dir = std::path::PathBuf::from(
-        std::env::var_os("OUT_DIR").ok_or_else(|| std::io::ErrorKind::NotFound)?,
-    );
+    let out_dir =
+        std::path::PathBuf::from(std::env::var_os("OUT_DIR").ok_or(std::io::ErrorKind::NotFound)?);
     let cmd = args::Args::command();
 
     let man = clap_mangen::Man::new(cmd);
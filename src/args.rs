@@ -5,9 +5,10 @@ use clap::ArgGroup;
 use clap::{Parser, Subcommand};
 use clap_verbosity_flag::Verbosity;
 
+use crate::nodes::{ArrayMerge, DelimiterSpacing, MultilineStyle, QuoteStyle, TagPolicy};
 use crate::property_file_reader::Delimiter;
 
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 #[command(
     author,
     version,
@@ -21,12 +22,32 @@ use crate::property_file_reader::Delimiter;
     \n\tprocon yaml example.properties
     \nJson -> Properties
     \n\tprocon properties example.json
+    \nToml -> Yaml
+    \n\tprocon yaml Cargo.toml
+    \nCompare Yaml and Properties
+    \n\tprocon diff application.yaml application.properties
+    \nRead a single value
+    \n\tprocon get server.port application.yaml
+    \nChange a single value
+    \n\tprocon set server.port=9090 application.properties
+    \nSelect parts of a file
+    \n\tprocon --select 'servers[*].host' json application.yaml
+    \nValidate against a json schema
+    \n\tprocon validate --schema schema.json application.yaml
+    \nYaml -> Dotenv
+    \n\tprocon dotenv application.yaml
+    \nProperties -> Kubernetes ConfigMap
+    \n\tprocon configmap --namespace prod -l app=demo application.properties
+    \nMulti-document Yaml with Spring profile -> Properties
+    \n\tprocon --profile prod properties application.yaml
+    \nMerge Yaml + Properties -> Json
+    \n\tprocon json application.yaml application-prod.properties
     "
 )]
 #[command(propagate_version = true)]
 #[command(group(ArgGroup::new("from")
 .multiple(false)
-.args(["from_property_file", "from_yaml_file", "from_json_file"]),
+.args(["from_property_file", "from_yaml_file", "from_json_file", "from_toml_file", "from_dotenv_file"]),
 ))]
 #[command(group(ArgGroup::new("dry-run")
 .multiple(false)
@@ -62,6 +83,77 @@ pub struct Args {
     #[arg(short = 'j', long)]
     pub from_json_file: bool,
 
+    /// Flag to specifying stdin bytes to be processed as toml
+    ///
+    /// Format of stdin bytes
+    #[arg(short = 't', long)]
+    pub from_toml_file: bool,
+
+    /// Flag to specifying stdin bytes to be processed as dotenv
+    ///
+    /// Format of stdin bytes
+    #[arg(short = 'e', long)]
+    pub from_dotenv_file: bool,
+
+    /// Only keep canonical numbers
+    ///
+    /// Numbers which a consumer can not read back unchanged like 1.10 or 64 bit ids are treated
+    /// as strings
+    #[arg(long, default_value_t = false)]
+    pub strict_numbers: bool,
+
+    /// Keys to always treat as strings
+    ///
+    /// Comma separated list of full keys like server.port or servers[0].id
+    #[arg(long, value_delimiter = ',')]
+    pub string_keys: Vec<String>,
+
+    /// Resolve ${key} and ${ENV_VAR:default} placeholders
+    ///
+    /// Placeholders refer to other keys, then to environment variables and at last to their
+    /// default value. Set writes the placeholders back unresolved
+    #[arg(long, default_value_t = false)]
+    pub interpolate: bool,
+
+    /// How arrays are merged if multiple input files are given
+    ///
+    /// replace: later arrays replace earlier ones, append: elements are appended,
+    /// index: elements are merged by their index
+    #[arg(long, default_value_t = ArrayMerge::Replace)]
+    pub array_merge: ArrayMerge,
+
+    /// How yaml tagged values like !Ref bucket are written to other formats
+    ///
+    /// keep: the tag is dropped and the value kept, stringify: a string like "!Ref bucket",
+    /// error: the conversion fails
+    #[arg(long, default_value_t = TagPolicy::Keep)]
+    pub tagged_values: TagPolicy,
+
+    /// Only convert the parts matching the query
+    ///
+    /// Dotted keys with wildcards like spring.datasource.* or servers[*].host, only used by the
+    /// conversion commands
+    #[arg(long)]
+    pub select: Option<String>,
+
+    /// Only read the yaml document with the given index of a multi-document file
+    #[arg(long, conflicts_with = "profile")]
+    pub document: Option<usize>,
+
+    /// Only read the yaml documents active for the given Spring profile
+    ///
+    /// Documents without spring.config.activate.on-profile or spring.profiles are always active,
+    /// all active documents are merged in order. Without this option only the documents without
+    /// profile activation are read
+    #[arg(long)]
+    pub profile: Option<String>,
+
+    /// Write every yaml document of a multi-document file to its own file
+    ///
+    /// The files are numbered like application-0.json
+    #[arg(long, default_value_t = false, conflicts_with_all = ["document", "profile"])]
+    pub split: bool,
+
     /// File to write the converted format to
     ///
     /// This option is mutual exclusive with the -d --dry-run option.
@@ -72,38 +164,288 @@ pub struct Args {
     pub verbose: Verbosity,
 }
 
-#[derive(Subcommand, Debug)]
+#[derive(Subcommand, Debug, Clone)]
 pub enum TargetFormat {
     /// Target format properties
     Properties {
         /// Property delimiter
         ///
-        /// only used in combination with properties command
-        #[arg(short, long, default_value_t = Delimiter::Equals)]
-        property_delimiter: Delimiter,
+        /// Overrides the delimiter detected per line, only used for properties input
+        #[arg(short, long)]
+        property_delimiter: Option<Delimiter>,
+
+        /// Write arrays in indexed form
+        ///
+        /// Every array element gets its own line like list[0]=value instead of a comma separated
+        /// value
+        #[arg(short, long, default_value_t = false)]
+        indexed_arrays: bool,
+
+        /// Write non-ASCII characters as \uXXXX escapes for ISO-8859-1 consumers
+        #[arg(long, default_value_t = false)]
+        escape_unicode: bool,
+
+        /// Continue lines longer than the given width on the next line with a \
+        #[arg(long, value_name = "WIDTH")]
+        line_width: Option<usize>,
+
+        /// Delimiter between the written keys and values
+        #[arg(long, default_value_t = Delimiter::Equals)]
+        output_delimiter: Delimiter,
+
+        /// Spaces around the written delimiter
+        ///
+        /// none: key=value, after: key= value, both: key = value
+        #[arg(long, default_value_t = DelimiterSpacing::None)]
+        delimiter_spacing: DelimiterSpacing,
+
+        /// Sort the keys alphabetically, array elements keep their order
+        #[arg(long, default_value_t = false)]
+        sort_keys: bool,
+
+        /// Separate top-level groups like server.* and spring.* with a blank line
+        #[arg(long, default_value_t = false)]
+        separate_groups: bool,
+
+        /// Comment written at the top of the file
+        #[arg(long)]
+        header: Option<String>,
+
         /// Input file
         file: PathBuf,
+
+        /// Further input files merged in order on top of the input file
+        merge_files: Vec<PathBuf>,
     },
 
     /// Target format yaml
     Yaml {
         /// Property delimiter
         ///
-        /// only used in combination with properties command
-        #[arg(short, long, default_value_t = Delimiter::Equals)]
-        property_delimiter: Delimiter,
+        /// Overrides the delimiter detected per line, only used for properties input
+        #[arg(short, long)]
+        property_delimiter: Option<Delimiter>,
+
+        /// Spaces per nesting level
+        #[arg(long, default_value_t = 2, value_parser = clap::value_parser!(u8).range(1..))]
+        indent: u8,
+
+        /// Write arrays of scalars in flow style like [a, b] if they are at most WIDTH characters
+        #[arg(long, value_name = "WIDTH")]
+        flow_arrays: Option<usize>,
+
+        /// Quoting of strings
+        ///
+        /// ambiguous: only strings which would be read as another type like "yes" or "8080" or
+        /// contain yaml syntax, always: every string
+        #[arg(long, default_value_t = QuoteStyle::Ambiguous)]
+        quote: QuoteStyle,
+
+        /// Style of strings with line breaks
+        ///
+        /// quoted: "a\nb", literal: a | block, folded: a > block
+        #[arg(long, default_value_t = MultilineStyle::Quoted)]
+        multiline: MultilineStyle,
+
+        /// Do not start the document with ---
+        #[arg(long, default_value_t = false)]
+        no_document_marker: bool,
 
         /// Input file
         file: PathBuf,
+
+        /// Further input files merged in order on top of the input file
+        merge_files: Vec<PathBuf>,
     },
 
     /// Target format json
     Json {
         /// Property delimiter
         ///
-        /// only used in combination with properties command
-        #[arg(short, long, default_value_t = Delimiter::Equals)]
-        property_delimiter: Delimiter,
+        /// Overrides the delimiter detected per line, only used for properties input
+        #[arg(short, long)]
+        property_delimiter: Option<Delimiter>,
+
+        /// Write comments of properties and yaml files as // comments (JSONC)
+        #[arg(long, default_value_t = false, conflicts_with_all = ["compact", "ndjson"])]
+        jsonc: bool,
+
+        /// Spaces per nesting level
+        #[arg(long, default_value_t = 1)]
+        indent: u8,
+
+        /// Write everything on a single line
+        #[arg(long, default_value_t = false)]
+        compact: bool,
+
+        /// Sort the keys alphabetically, array elements keep their order
+        #[arg(long, default_value_t = false)]
+        sort_keys: bool,
+
+        /// Write non-ASCII characters of keys, values and comments as \uXXXX escapes
+        #[arg(long, default_value_t = false)]
+        ascii: bool,
+
+        /// Write dotted keys like {"server.port": 8080} instead of nested objects
+        ///
+        /// Array elements are addressed with [index] like servers[0].host
+        #[arg(long, default_value_t = false)]
+        flat: bool,
+
+        /// Write every element of a root list compact on its own line (NDJSON)
+        ///
+        /// Other files are written as a single line
+        #[arg(long, default_value_t = false)]
+        ndjson: bool,
+
+        /// Input file
+        file: PathBuf,
+
+        /// Further input files merged in order on top of the input file
+        merge_files: Vec<PathBuf>,
+    },
+
+    /// Target format toml
+    Toml {
+        /// Property delimiter
+        ///
+        /// Overrides the delimiter detected per line, only used for properties input
+        #[arg(short, long)]
+        property_delimiter: Option<Delimiter>,
+
+        /// Input file
+        file: PathBuf,
+
+        /// Further input files merged in order on top of the input file
+        merge_files: Vec<PathBuf>,
+    },
+
+    /// Target format dotenv
+    ///
+    /// Keys are written as environment variables like SERVER_PORT=8080
+    Dotenv {
+        /// Property delimiter
+        ///
+        /// Overrides the delimiter detected per line, only used for properties input
+        #[arg(short, long)]
+        property_delimiter: Option<Delimiter>,
+
+        /// Input file
+        file: PathBuf,
+
+        /// Further input files merged in order on top of the input file
+        merge_files: Vec<PathBuf>,
+    },
+
+    /// Target format Kubernetes ConfigMap or Secret
+    ///
+    /// The converted file is embedded as a single data entry unless --flat is given
+    #[command(name = "configmap")]
+    ConfigMap {
+        /// Property delimiter
+        ///
+        /// Overrides the delimiter detected per line, only used for properties input
+        #[arg(short, long)]
+        property_delimiter: Option<Delimiter>,
+
+        /// Name of the ConfigMap, defaults to the input file name
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Namespace of the ConfigMap
+        #[arg(long)]
+        namespace: Option<String>,
+
+        /// Comma separated labels like app=demo,tier=backend
+        #[arg(short, long, value_delimiter = ',')]
+        labels: Vec<String>,
+
+        /// Write a Secret with base64 encoded values
+        #[arg(short, long, default_value_t = false)]
+        secret: bool,
+
+        /// Write every key as its own data entry
+        #[arg(short, long, default_value_t = false, conflicts_with = "embed")]
+        flat: bool,
+
+        /// Format of the embedded file
+        #[arg(short, long, default_value = "properties", value_parser = ["properties", "yaml"])]
+        embed: String,
+
+        /// Input file
+        file: PathBuf,
+
+        /// Further input files merged in order on top of the input file
+        merge_files: Vec<PathBuf>,
+    },
+
+    /// Compare two files of any supported format
+    ///
+    /// Lists added, removed and changed keys by their dotted path, exits with a non-zero code if
+    /// the files differ
+    Diff {
+        /// Property delimiter
+        ///
+        /// Overrides the delimiter detected per line, only used for properties input
+        #[arg(short, long)]
+        property_delimiter: Option<Delimiter>,
+
+        /// Print the differences as json
+        #[arg(long, default_value_t = false)]
+        json: bool,
+
+        /// Input file
+        file: PathBuf,
+
+        /// File to compare the input file with
+        other_file: PathBuf,
+    },
+
+    /// Print the value of a dotted key like server.port or servers[0].host
+    Get {
+        /// Property delimiter
+        ///
+        /// Overrides the delimiter detected per line, only used for properties input
+        #[arg(short, long)]
+        property_delimiter: Option<Delimiter>,
+
+        /// Dotted key, array elements are addressed with [index]
+        key: String,
+
+        /// Input file
+        file: PathBuf,
+    },
+
+    /// Set the value of a dotted key and write the file back in its format
+    Set {
+        /// Property delimiter
+        ///
+        /// Overrides the delimiter detected per line, only used for properties input
+        #[arg(short, long)]
+        property_delimiter: Option<Delimiter>,
+
+        /// Dotted key and new value like server.port=9090
+        #[arg(value_name = "KEY=VALUE")]
+        assignment: String,
+
+        /// Input file
+        file: PathBuf,
+    },
+
+    /// Validate a file of any supported format against a json schema
+    ///
+    /// Lists every violation with its dotted key and line number, exits with a non-zero code if
+    /// the file is invalid
+    Validate {
+        /// Property delimiter
+        ///
+        /// Overrides the delimiter detected per line, only used for properties input
+        #[arg(short, long)]
+        property_delimiter: Option<Delimiter>,
+
+        /// Json schema file
+        #[arg(short, long)]
+        schema: PathBuf,
 
         /// Input file
         file: PathBuf,
@@ -123,19 +465,61 @@ impl TargetFormat {
             TargetFormat::Properties { file, .. } => file,
             TargetFormat::Json { file, .. } => file,
             TargetFormat::Yaml { file, .. } => file,
+            TargetFormat::Toml { file, .. } => file,
+            TargetFormat::Dotenv { file, .. } => file,
+            TargetFormat::ConfigMap { file, .. } => file,
+            TargetFormat::Diff { file, .. } => file,
+            TargetFormat::Get { file, .. } => file,
+            TargetFormat::Set { file, .. } => file,
+            TargetFormat::Validate { file, .. } => file,
+        }
+    }
+    pub fn merge_files(&self) -> &[PathBuf] {
+        match self {
+            TargetFormat::Properties { merge_files, .. } => merge_files,
+            TargetFormat::Json { merge_files, .. } => merge_files,
+            TargetFormat::Yaml { merge_files, .. } => merge_files,
+            TargetFormat::Toml { merge_files, .. } => merge_files,
+            TargetFormat::Dotenv { merge_files, .. } => merge_files,
+            TargetFormat::ConfigMap { merge_files, .. } => merge_files,
+            TargetFormat::Diff { .. }
+            | TargetFormat::Get { .. }
+            | TargetFormat::Set { .. }
+            | TargetFormat::Validate { .. } => &[],
         }
     }
     pub fn delimiter(&self) -> Option<&Delimiter> {
         match self {
             TargetFormat::Properties {
                 property_delimiter, ..
-            } => Some(property_delimiter),
+            } => property_delimiter.as_ref(),
             TargetFormat::Yaml {
                 property_delimiter, ..
-            } => Some(property_delimiter),
+            } => property_delimiter.as_ref(),
             TargetFormat::Json {
                 property_delimiter, ..
-            } => Some(property_delimiter),
+            } => property_delimiter.as_ref(),
+            TargetFormat::Toml {
+                property_delimiter, ..
+            } => property_delimiter.as_ref(),
+            TargetFormat::Dotenv {
+                property_delimiter, ..
+            } => property_delimiter.as_ref(),
+            TargetFormat::ConfigMap {
+                property_delimiter, ..
+            } => property_delimiter.as_ref(),
+            TargetFormat::Diff {
+                property_delimiter, ..
+            } => property_delimiter.as_ref(),
+            TargetFormat::Get {
+                property_delimiter, ..
+            } => property_delimiter.as_ref(),
+            TargetFormat::Set {
+                property_delimiter, ..
+            } => property_delimiter.as_ref(),
+            TargetFormat::Validate {
+                property_delimiter, ..
+            } => property_delimiter.as_ref(),
         }
     }
 }
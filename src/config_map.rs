@@ -0,0 +1,108 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+use crate::errors::ProconError;
+use crate::node::{Node, NodeType};
+use crate::nodes::{MultilineStyle, Nodes};
+use crate::yaml_writer::YamlStyle;
+
+#[cfg(test)]
+#[path = "./config_map_test.rs"]
+mod config_map_test;
+
+/// Metadata of a Kubernetes ConfigMap or Secret manifest
+pub struct Manifest<'a> {
+    pub name: &'a str,
+    pub namespace: Option<&'a str>,
+    /// labels like `app=demo`
+    pub labels: &'a [String],
+    pub secret: bool,
+}
+
+impl Manifest<'_> {
+    /// Nodes of the manifest with the given data entries, Secret values are base64 encoded
+    pub fn to_nodes(&self, data: &[(String, String)]) -> Result<Nodes, ProconError> {
+        let mut nodes = Nodes::new();
+        nodes.push(root_node("apiVersion", "v1"));
+        if self.secret {
+            nodes.push(root_node("kind", "Secret"));
+        } else {
+            nodes.push(root_node("kind", "ConfigMap"));
+        }
+
+        let mut metadata = Node::new_from_name("metadata");
+        let name = child_node(&mut metadata, "name", self.name);
+        metadata.children.push(name);
+        if let Some(namespace) = self.namespace {
+            let namespace = child_node(&mut metadata, "namespace", namespace);
+            metadata.children.push(namespace);
+        }
+        if !self.labels.is_empty() {
+            let mut labels = Node::new_child(1, &mut metadata, "labels");
+            for label in self.labels {
+                let (key, value) = label.split_once('=').ok_or(ProconError {
+                    message: ["Expected label like key=value: ", label].concat(),
+                })?;
+                let label = child_node(&mut labels, key, value);
+                labels.children.push(label);
+            }
+            metadata.children.push(labels);
+        }
+        nodes.push(metadata);
+
+        if self.secret {
+            nodes.push(root_node("type", "Opaque"));
+        }
+        let mut data_node = Node::new_from_name("data");
+        for (key, value) in data {
+            let value = if self.secret {
+                STANDARD.encode(value)
+            } else {
+                value.clone()
+            };
+            let entry = child_node(&mut data_node, key, &value);
+            data_node.children.push(entry);
+        }
+        nodes.push(data_node);
+        Ok(nodes)
+    }
+}
+
+/// Yaml style of the manifest, embedded files are written as literal blocks
+pub(crate) fn manifest_style() -> YamlStyle {
+    YamlStyle {
+        multiline: MultilineStyle::Literal,
+        ..YamlStyle::default()
+    }
+}
+
+/// Data key of a flattened key, ConfigMap keys only allow alphanumerics, `-`, `_` and `.` so
+/// indices become segments and every other character is replaced by `_`
+pub fn data_key(key: &str) -> String {
+    key.replace('[', ".")
+        .replace(']', "")
+        .chars()
+        .map(|character| match character {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' => character,
+            _ => '_',
+        })
+        .collect()
+}
+
+/// Lowercase name with dashes as required for Kubernetes object names
+pub fn object_name(name: &str) -> String {
+    name.to_lowercase().replace(['_', ' ', '.'], "-")
+}
+
+fn root_node(name: &str, value: &str) -> Node {
+    let mut node = Node::new_from_name(name);
+    node.value = NodeType::STRING(value.to_string());
+    node
+}
+
+fn child_node(parent: &mut Node, name: &str, value: &str) -> Node {
+    let mut node = Node::new_child(parent.level + 1, parent, name);
+    // manifest values are always strings
+    node.value = NodeType::STRING(value.to_string());
+    node
+}
@@ -0,0 +1,88 @@
+#[cfg(test)]
+mod tests {
+    use crate::config_map::{data_key, manifest_style, object_name, Manifest};
+    use crate::nodes_writer::yaml_content;
+    use crate::yaml_writer::YamlStyle;
+
+    #[test]
+    fn config_map_manifest() {
+        let labels = vec![String::from("app=demo")];
+        let manifest = Manifest {
+            name: "demo",
+            namespace: Some("prod"),
+            labels: &labels,
+            secret: false,
+        };
+        let data = vec![(String::from("server.port"), String::from("8080"))];
+
+        let content =
+            yaml_content(&manifest.to_nodes(&data).unwrap(), &YamlStyle::default()).unwrap();
+        assert_eq!(
+            "---\napiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: demo\n  namespace: prod\n  labels:\n    app: demo\ndata:\n  server.port: \"8080\"",
+            content
+        );
+    }
+
+    #[test]
+    fn secret_manifest() {
+        let manifest = Manifest {
+            name: "demo",
+            namespace: None,
+            labels: &[],
+            secret: true,
+        };
+        let data = vec![(
+            String::from("application.properties"),
+            String::from("password=secret\n"),
+        )];
+
+        let content =
+            yaml_content(&manifest.to_nodes(&data).unwrap(), &YamlStyle::default()).unwrap();
+        assert_eq!(
+            "---\napiVersion: v1\nkind: Secret\nmetadata:\n  name: demo\ntype: Opaque\ndata:\n  application.properties: cGFzc3dvcmQ9c2VjcmV0Cg==",
+            content
+        );
+    }
+
+    #[test]
+    fn embedded_file_literal_block() {
+        let manifest = Manifest {
+            name: "demo",
+            namespace: None,
+            labels: &[],
+            secret: false,
+        };
+        let data = vec![(
+            String::from("application.properties"),
+            String::from("server.port=8080\nserver.host=localhost\n"),
+        )];
+
+        let content = yaml_content(&manifest.to_nodes(&data).unwrap(), &manifest_style()).unwrap();
+        assert_eq!(
+            "---\napiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: demo\ndata:\n  application.properties: |\n    server.port=8080\n    server.host=localhost\n",
+            content
+        );
+    }
+
+    #[test]
+    fn invalid_label() {
+        let labels = vec![String::from("app")];
+        let manifest = Manifest {
+            name: "demo",
+            namespace: None,
+            labels: &labels,
+            secret: false,
+        };
+
+        assert!(manifest.to_nodes(&[]).is_err());
+    }
+
+    #[test]
+    fn kubernetes_names() {
+        assert_eq!("servers.0.host", data_key("servers[0].host"));
+        assert_eq!("matrix.0.1", data_key("matrix[0][1]"));
+        assert_eq!("server_name.gr__e", data_key("server name.größe"));
+        assert_eq!("a_b_c", data_key("a/b:c"));
+        assert_eq!("my-app-prod", object_name("My_App.prod"));
+    }
+}
@@ -0,0 +1,87 @@
+use log::{info, trace};
+
+use crate::args::Args;
+use crate::errors::ProconError;
+use crate::nodes::Nodes;
+use crate::property_file_reader::PropertyFileReader;
+
+#[cfg(test)]
+#[path = "./dotenv_file_reader_test.rs"]
+mod dotenv_file_reader_test;
+
+pub struct DotenvFileReader {}
+
+impl DotenvFileReader {
+    pub fn parse(_args: &Args, content: &str) -> Result<Nodes, ProconError> {
+        info!("Use DotenvFileReader");
+        let mut nodes = Nodes::new();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                trace!("Ignore line {}", index + 1);
+                continue;
+            }
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (variable, value) = line.split_once('=').ok_or(ProconError {
+                message: ["Missing = in line ", &(index + 1).to_string()].concat(),
+            })?;
+
+            let key = Self::property_key(variable.trim());
+            let value = Self::unquote(value.trim(), index + 1)?;
+            if let Some(mut node) = PropertyFileReader::property_to_node(&key, &value) {
+                nodes.merge(&mut node);
+            }
+        }
+        Ok(nodes)
+    }
+
+    /// Reverse of the relaxed binding, `SERVERS_0_HOST` becomes `servers[0].host`
+    pub fn property_key(variable: &str) -> String {
+        let mut key = String::new();
+        for part in variable.split('_').filter(|part| !part.is_empty()) {
+            if part.parse::<usize>().is_ok() {
+                key.push_str(&["[", part, "]"].concat());
+            } else {
+                if !key.is_empty() {
+                    key.push('.');
+                }
+                key.push_str(&part.to_lowercase());
+            }
+        }
+        key
+    }
+
+    /// Single quoted values are taken literally, double quoted values support `\` escapes and
+    /// unquoted values end at an inline comment
+    fn unquote(value: &str, line_number: usize) -> Result<String, ProconError> {
+        let unclosed = || ProconError {
+            message: ["Unclosed quote in line ", &line_number.to_string()].concat(),
+        };
+
+        if let Some(quoted) = value.strip_prefix('\'') {
+            let (literal, _) = quoted.split_once('\'').ok_or_else(unclosed)?;
+            return Ok(literal.to_string());
+        }
+        if let Some(quoted) = value.strip_prefix('"') {
+            let mut unquoted = String::new();
+            let mut characters = quoted.chars();
+            while let Some(character) = characters.next() {
+                match character {
+                    '"' => return Ok(unquoted),
+                    '\\' => match characters.next().ok_or_else(unclosed)? {
+                        'n' => unquoted.push('\n'),
+                        't' => unquoted.push('\t'),
+                        escaped => unquoted.push(escaped),
+                    },
+                    _ => unquoted.push(character),
+                }
+            }
+            return Err(unclosed());
+        }
+        let value = match value.find(" #") {
+            Some(comment) => &value[..comment],
+            None => value,
+        };
+        Ok(value.trim_end().to_string())
+    }
+}
@@ -0,0 +1,35 @@
+#[cfg(test)]
+mod tests {
+    use crate::dotenv_file_reader::DotenvFileReader;
+
+    #[test]
+    fn property_key() {
+        assert_eq!("server.port", DotenvFileReader::property_key("SERVER_PORT"));
+        assert_eq!(
+            "servers[0].host",
+            DotenvFileReader::property_key("SERVERS_0_HOST")
+        );
+        assert_eq!("matrix[0][1]", DotenvFileReader::property_key("MATRIX_0_1"));
+        assert_eq!("db.url", DotenvFileReader::property_key("DB__URL"));
+    }
+
+    #[test]
+    fn unquote() {
+        assert_eq!("plain", DotenvFileReader::unquote("plain", 1).unwrap());
+        assert_eq!(
+            "plain",
+            DotenvFileReader::unquote("plain # comment", 1).unwrap()
+        );
+        assert_eq!("a#b", DotenvFileReader::unquote("a#b", 1).unwrap());
+        assert_eq!(
+            "single ${quoted} \\n",
+            DotenvFileReader::unquote("'single ${quoted} \\n' # comment", 1).unwrap()
+        );
+        assert_eq!(
+            "double \"quoted\"\nline",
+            DotenvFileReader::unquote("\"double \\\"quoted\\\"\\nline\"", 1).unwrap()
+        );
+        assert!(DotenvFileReader::unquote("\"unclosed", 1).is_err());
+        assert!(DotenvFileReader::unquote("'unclosed", 1).is_err());
+    }
+}
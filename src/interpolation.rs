@@ -0,0 +1,153 @@
+use std::env;
+
+use log::debug;
+
+use crate::errors::ProconError;
+use crate::node::{Node, NodeType};
+use crate::nodes::Nodes;
+
+#[cfg(test)]
+#[path = "./interpolation_test.rs"]
+mod interpolation_test;
+
+/// Resolves `${key}` and `${key:default}` placeholders of all string values
+///
+/// A placeholder refers to another key of the same nodes, then to an environment variable and
+/// at last to its default value. A value consisting of a single placeholder to another key
+/// keeps the type of the referenced value.
+pub fn interpolate(nodes: &mut Nodes) -> Result<(), ProconError> {
+    let resolver = Resolver {
+        nodes: nodes.clone(),
+    };
+    for node in nodes.iter_mut() {
+        let key = node.name.clone();
+        interpolate_node(node, &key, &resolver)?;
+    }
+    Ok(())
+}
+
+fn interpolate_node(node: &mut Node, key: &str, resolver: &Resolver) -> Result<(), ProconError> {
+    match &mut node.value {
+        NodeType::STRING(value) if value.contains("${") => {
+            node.value = resolver.resolve_value(value, &mut vec![key.to_string()])?;
+        }
+        NodeType::ARRAY(elements) => {
+            for (index, element) in elements.iter_mut().enumerate() {
+                let element_key = [key, "[", &index.to_string(), "]"].concat();
+                interpolate_node(element, &element_key, resolver)?;
+            }
+        }
+        _ => {}
+    }
+    for child in &mut node.children {
+        let child_key = if key.is_empty() {
+            child.name.clone()
+        } else {
+            [key, ".", &child.name].concat()
+        };
+        interpolate_node(child, &child_key, resolver)?;
+    }
+    Ok(())
+}
+
+/// Placeholder lookups on the nodes before interpolation
+struct Resolver {
+    nodes: Nodes,
+}
+
+impl Resolver {
+    /// `keys` is the chain of keys currently being resolved, the last one is the current key
+    fn resolve_value(&self, value: &str, keys: &mut Vec<String>) -> Result<NodeType, ProconError> {
+        // a single placeholder keeps the type of the referenced value
+        if let Some((start, end)) = next_placeholder(value, keys)? {
+            if start == 0 && end == value.len() {
+                return self.resolve_placeholder(&value[2..end - 1], keys);
+            }
+        }
+
+        let mut resolved = String::new();
+        let mut rest = value;
+        while let Some((start, end)) = next_placeholder(rest, keys)? {
+            resolved.push_str(&rest[..start]);
+            let placeholder_value = self.resolve_placeholder(&rest[start + 2..end - 1], keys)?;
+            resolved.push_str(&placeholder_value.to_string());
+            rest = &rest[end..];
+        }
+        resolved.push_str(rest);
+        Ok(NodeType::STRING(resolved))
+    }
+
+    fn resolve_placeholder(
+        &self,
+        placeholder: &str,
+        keys: &mut Vec<String>,
+    ) -> Result<NodeType, ProconError> {
+        let (name, default) = match placeholder.split_once(':') {
+            Some((name, default)) => (name, Some(default)),
+            None => (placeholder, None),
+        };
+        debug!("Resolve placeholder {} of {}", name, keys.last().unwrap());
+
+        if keys.iter().any(|key| key == name) {
+            keys.push(name.to_string());
+            return Err(ProconError {
+                message: ["Cyclic placeholder: ", &keys.join(" -> ")].concat(),
+            });
+        }
+
+        if let Some(node) = self.nodes.find(name) {
+            if node.value.is_scalar() {
+                keys.push(name.to_string());
+                let value = match &node.value {
+                    NodeType::STRING(value) => self.resolve_value(value, keys)?,
+                    value => value.clone(),
+                };
+                keys.pop();
+                return Ok(value);
+            }
+        }
+        if let Ok(value) = env::var(name) {
+            return Ok(NodeType::STRING(value));
+        }
+        match default {
+            Some(default) => self.resolve_value(default, keys),
+            None => Err(ProconError {
+                message: [
+                    "Unresolved placeholder ${",
+                    placeholder,
+                    "} in ",
+                    keys.last().unwrap(),
+                ]
+                .concat(),
+            }),
+        }
+    }
+}
+
+/// Start and end (exclusive) of the next `${...}` placeholder, nested placeholders in defaults
+/// are part of the outer one
+fn next_placeholder(value: &str, keys: &[String]) -> Result<Option<(usize, usize)>, ProconError> {
+    let Some(start) = value.find("${") else {
+        return Ok(None);
+    };
+    let mut depth = 0;
+    for (index, character) in value[start..].char_indices() {
+        match character {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(Some((start, start + index + 1)));
+                }
+            }
+            _ => {}
+        }
+    }
+    Err(ProconError {
+        message: [
+            "Unclosed placeholder in ",
+            keys.last().map(String::as_str).unwrap_or_default(),
+        ]
+        .concat(),
+    })
+}
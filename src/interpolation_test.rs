@@ -0,0 +1,98 @@
+#[cfg(test)]
+mod tests {
+    use crate::interpolation::interpolate;
+    use crate::node::{Node, NodeType};
+    use crate::nodes::Nodes;
+    use crate::property_file_reader::PropertyFileReader;
+
+    fn nodes(properties: &[(&str, &str)]) -> Nodes {
+        let mut nodes = Nodes::new();
+        for (key, value) in properties {
+            let mut node: Node = PropertyFileReader::property_to_node(key, value).unwrap();
+            nodes.merge(&mut node);
+        }
+        nodes
+    }
+
+    fn value(nodes: &Nodes, key: &str) -> NodeType {
+        nodes.find(key).unwrap().value.clone()
+    }
+
+    #[test]
+    fn interpolate_references() {
+        let mut nodes = nodes(&[
+            ("db.host", "localhost"),
+            ("db.port", "5432"),
+            ("db.url", "jdbc:postgresql://${db.host}:${db.port}/app"),
+            ("server.port", "${db.port}"),
+            ("hosts", "${db.host},remote"),
+        ]);
+
+        interpolate(&mut nodes).unwrap();
+        assert_eq!(
+            NodeType::STRING(String::from("jdbc:postgresql://localhost:5432/app")),
+            value(&nodes, "db.url")
+        );
+        // a single placeholder keeps the type
+        assert_eq!(
+            NodeType::NUMERIC(String::from("5432")),
+            value(&nodes, "server.port")
+        );
+        assert_eq!(
+            NodeType::STRING(String::from("localhost")),
+            value(&nodes, "hosts[0]")
+        );
+    }
+
+    #[test]
+    fn interpolate_environment_and_defaults() {
+        std::env::set_var("PROCON_TEST_HOST", "env-host");
+        let mut nodes = nodes(&[
+            ("host", "${PROCON_TEST_HOST:fallback}"),
+            ("user", "${PROCON_TEST_UNSET_USER:admin}"),
+            ("url", "http://${PROCON_TEST_UNSET_URL:${host}}/"),
+            ("empty", "${PROCON_TEST_UNSET_EMPTY:}"),
+        ]);
+
+        interpolate(&mut nodes).unwrap();
+        assert_eq!(
+            NodeType::STRING(String::from("env-host")),
+            value(&nodes, "host")
+        );
+        assert_eq!(
+            NodeType::STRING(String::from("admin")),
+            value(&nodes, "user")
+        );
+        assert_eq!(
+            NodeType::STRING(String::from("http://env-host/")),
+            value(&nodes, "url")
+        );
+        assert_eq!(NodeType::STRING(String::new()), value(&nodes, "empty"));
+    }
+
+    #[test]
+    fn interpolate_cycle() {
+        let mut nodes = nodes(&[("a", "${b}"), ("b", "x${c}"), ("c", "${a}")]);
+
+        let error = interpolate(&mut nodes).unwrap_err();
+        assert_eq!("Cyclic placeholder: a -> b -> c -> a", error.message);
+    }
+
+    #[test]
+    fn interpolate_unresolved() {
+        let mut nodes = nodes(&[("server.url", "http://${PROCON_TEST_UNSET_HOST}")]);
+
+        let error = interpolate(&mut nodes).unwrap_err();
+        assert_eq!(
+            "Unresolved placeholder ${PROCON_TEST_UNSET_HOST} in server.url",
+            error.message
+        );
+    }
+
+    #[test]
+    fn interpolate_unclosed() {
+        let mut nodes = nodes(&[("server.url", "http://${host")]);
+
+        assert!(interpolate(&mut nodes).is_err());
+    }
+}
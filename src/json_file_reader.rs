@@ -6,12 +6,16 @@ use crate::errors::ProconError;
 use crate::node::{Node, NodeType};
 use crate::nodes::Nodes;
 
+#[cfg(test)]
+#[path = "./json_file_reader_test.rs"]
+mod json_file_reader_test;
+
 pub struct JsonFileReader {}
 
 impl JsonFileReader {
-    pub fn parse(_args: &Args, content: &String) -> Result<Nodes, ProconError> {
+    pub fn parse(_args: &Args, content: &str) -> Result<Nodes, ProconError> {
         info!("Use JsonFileReader");
-        let json_data: Value = serde_json::from_str(&content).map_err(|_| ProconError {
+        let json_data: Value = serde_json::from_str(content).map_err(|_| ProconError {
             message: "Unable to parse".to_string(),
         })?;
         Self::convert_json_values_to_nodes(&json_data)
@@ -21,17 +25,13 @@ impl JsonFileReader {
         match json_data {
             Value::Object(ref obj) => {
                 for (key, value) in obj.iter() {
-                    let mut parent = Self::json_to_node(&key, value, None, 0).unwrap();
+                    let mut parent = Self::json_to_node(key, value, None, 0).unwrap();
                     nodes.merge(&mut parent);
                 }
             }
             Value::Array(obj) => {
                 let mut parent = Node::new_from_name("");
-                let mut children: Vec<String> = vec![];
-                for value in obj.iter() {
-                    children.push(value.to_string().replace("\"", ""));
-                }
-                parent.value = NodeType::ARRAY(children);
+                parent.value = NodeType::ARRAY(Self::json_array_to_nodes(obj, &mut parent, 0));
                 nodes.merge(&mut parent);
             }
             _ => error!("not valid json"),
@@ -54,16 +54,17 @@ impl JsonFileReader {
 
         // get values
         let new_node_option = match value {
+            // json values are typed, no need to guess the type
             Value::String(json_value) => {
-                new_node.value = NodeType::parse(json_value);
-                return Some(new_node);
+                new_node.value = NodeType::STRING(json_value.clone());
+                Some(new_node)
             }
             Value::Bool(json_value) => {
-                new_node.value = NodeType::parse(&json_value.to_string());
+                new_node.value = NodeType::BOOLEAN(*json_value);
                 Some(new_node)
             }
             Value::Number(json_value) => {
-                new_node.value = NodeType::parse(&json_value.to_string());
+                new_node.value = NodeType::NUMERIC(json_value.to_string());
                 Some(new_node)
             }
             Value::Object(json_value) => {
@@ -71,24 +72,34 @@ impl JsonFileReader {
                 for (map_key, map_value) in json_value.iter() {
                     let child_node =
                         Self::json_to_node(map_key, map_value, Some(&mut new_node), level + 1);
-                    if child_node.is_some() {
-                        children.push(child_node.unwrap());
+                    if let Some(child_node) = child_node {
+                        children.push(child_node);
                     }
                 }
                 new_node.children = children;
                 Some(new_node)
             }
             Value::Array(json_value) => {
-                let mut children: Vec<String> = vec![];
-                for value in json_value.iter() {
-                    debug!("{}", value);
-                    children.push(value.to_string().replace("\"", ""));
-                }
-                new_node.value = NodeType::ARRAY(children);
+                new_node.value =
+                    NodeType::ARRAY(Self::json_array_to_nodes(json_value, &mut new_node, level));
+                Some(new_node)
+            }
+            Value::Null => {
+                new_node.value = NodeType::NULL;
                 Some(new_node)
             }
-            Value::Null => None,
         };
         new_node_option
     }
+    fn json_array_to_nodes(array: &[Value], parent: &mut Node, level: usize) -> Vec<Node> {
+        let mut elements: Vec<Node> = vec![];
+        for (index, value) in array.iter().enumerate() {
+            debug!("{}", value);
+            let element = Self::json_to_node(&index.to_string(), value, Some(parent), level + 1);
+            if let Some(element) = element {
+                elements.push(element);
+            }
+        }
+        elements
+    }
 }
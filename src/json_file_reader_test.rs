@@ -3,6 +3,7 @@ mod tests {
     use serde_json::Value;
 
     use crate::json_file_reader::JsonFileReader;
+    use crate::node::NodeType::{BOOLEAN, NULL, NUMERIC, STRING};
 
     #[test]
     fn convert_json_values_to_nodes_multiple_nodes() {
@@ -13,17 +14,17 @@ mod tests {
 
         let reader_node = nodes.get(0).unwrap();
         assert_eq!("reader", reader_node.name);
-        let datasource_node = reader_node.children.get(0).unwrap();
+        let datasource_node = reader_node.children.first().unwrap();
         assert_eq!("datasource", datasource_node.name);
-        let jdbc_url_node = datasource_node.children.get(0).unwrap();
+        let jdbc_url_node = datasource_node.children.first().unwrap();
         assert_eq!("jdbc-url", jdbc_url_node.name);
         assert_eq!("localhost", jdbc_url_node.value.to_string());
 
         let writer_node = nodes.get(1).unwrap();
         assert_eq!("writer", writer_node.name);
-        let datasource_node = writer_node.children.get(0).unwrap();
+        let datasource_node = writer_node.children.first().unwrap();
         assert_eq!("datasource", datasource_node.name);
-        let jdbc_url_node = datasource_node.children.get(0).unwrap();
+        let jdbc_url_node = datasource_node.children.first().unwrap();
         assert_eq!("jdbc-url", jdbc_url_node.name);
         assert_eq!("localhost", jdbc_url_node.value.to_string());
     }
@@ -109,4 +110,39 @@ mod tests {
 
         assert_eq!(None, nodes.get(0));
     }
+
+    #[test]
+    fn convert_json_values_to_nodes_typed_strings() {
+        let content = String::from("{\"flag\": \"true\", \"list\": \"1,2\", \"zip\": \"08\"}");
+        let json_data: Value = serde_json::from_str(&content).expect("Unable to parse");
+
+        let nodes = JsonFileReader::convert_json_values_to_nodes(&json_data).unwrap();
+
+        assert_eq!(STRING("true".to_string()), nodes.get(0).unwrap().value);
+        assert_eq!(STRING("1,2".to_string()), nodes.get(1).unwrap().value);
+        assert_eq!(STRING("08".to_string()), nodes.get(2).unwrap().value);
+    }
+
+    #[test]
+    fn convert_json_values_to_nodes_typed_scalars() {
+        let content = String::from("{\"flag\": true, \"port\": 8080}");
+        let json_data: Value = serde_json::from_str(&content).expect("Unable to parse");
+
+        let nodes = JsonFileReader::convert_json_values_to_nodes(&json_data).unwrap();
+
+        assert_eq!(BOOLEAN(true), nodes.get(0).unwrap().value);
+        assert_eq!(NUMERIC("8080".to_string()), nodes.get(1).unwrap().value);
+    }
+
+    #[test]
+    fn convert_json_values_to_nodes_null() {
+        let content = String::from("{\"isReader\": null}");
+        let json_data: Value = serde_json::from_str(&content).expect("Unable to parse");
+
+        let nodes = JsonFileReader::convert_json_values_to_nodes(&json_data).unwrap();
+
+        let reader_node = nodes.get(0).unwrap();
+        assert_eq!("isReader", reader_node.name);
+        assert_eq!(NULL, reader_node.value);
+    }
 }
@@ -0,0 +1,224 @@
+use json::JsonValue;
+
+use crate::node::{Node, NodeType};
+use crate::nodes::Nodes;
+
+#[cfg(test)]
+#[path = "./json_writer_test.rs"]
+mod json_writer_test;
+
+/// Options of the json writer
+#[derive(Debug, Clone)]
+pub(crate) struct JsonStyle {
+    /// spaces per nesting level
+    pub(crate) indent: usize,
+    /// everything on a single line
+    pub(crate) compact: bool,
+    /// keys are sorted alphabetically, array elements keep their order
+    pub(crate) sort_keys: bool,
+    /// non-ASCII characters are written as `\uXXXX`
+    pub(crate) ascii: bool,
+    /// dotted keys like `server.port` instead of nested objects
+    pub(crate) flat: bool,
+    /// every element of a root list is written compact on its own line
+    pub(crate) ndjson: bool,
+    /// comments of the nodes as `//` lines (JSONC)
+    pub(crate) comments: bool,
+}
+
+impl Default for JsonStyle {
+    fn default() -> Self {
+        JsonStyle {
+            indent: 1,
+            compact: false,
+            sort_keys: false,
+            ascii: false,
+            flat: false,
+            ndjson: false,
+            comments: false,
+        }
+    }
+}
+
+/// Json emitter for the node tree, optionally with the comments of the nodes (JSONC)
+///
+/// Follows the output of `JsonValue::pretty(1)` by default
+pub(crate) struct JsonWriter<'a> {
+    content: String,
+    level: usize,
+    style: &'a JsonStyle,
+}
+
+impl<'a> JsonWriter<'a> {
+    pub(crate) fn write(nodes: &Nodes, style: &'a JsonStyle) -> String {
+        let root_list = nodes.iter().find(|node| node.name.is_empty());
+        match (style.ndjson, root_list.map(|node| &node.value)) {
+            // every element is a document of its own
+            (true, Some(NodeType::ARRAY(elements))) => elements
+                .iter()
+                .map(|element| Self::write_document(&document_nodes(element), style))
+                .collect::<Vec<String>>()
+                .join("\n"),
+            _ => Self::write_document(nodes, style),
+        }
+    }
+
+    fn write_document(nodes: &Nodes, style: &'a JsonStyle) -> String {
+        // flat nodes are new nodes without the comments at the end of the file
+        let trailing_comments = nodes.trailing_comments.clone();
+        // sorted before flattening, flat keys keep the order of the array indices
+        let sorted_nodes;
+        let mut nodes = match style.sort_keys {
+            true => {
+                let mut nodes = nodes.clone();
+                nodes.sort();
+                sorted_nodes = nodes;
+                &sorted_nodes
+            }
+            false => nodes,
+        };
+        let flat_nodes;
+        if style.flat {
+            flat_nodes = nodes.flat();
+            nodes = &flat_nodes;
+        }
+
+        let mut writer = JsonWriter {
+            content: String::new(),
+            level: 0,
+            style,
+        };
+        // root list treatment
+        match nodes.iter().find(|node| node.name.is_empty()) {
+            Some(root_list) => writer.emit_node(root_list),
+            None => {
+                let entries: Vec<&Node> = nodes.iter().collect();
+                writer.emit_entries(&entries, true, ('{', '}'));
+            }
+        }
+        if style.comments && !writer.compact() {
+            for comment in &trailing_comments {
+                writer.content.push('\n');
+                writer.push_json(&comment_text(comment));
+            }
+        }
+        writer.content
+    }
+
+    fn compact(&self) -> bool {
+        self.style.compact || self.style.ndjson
+    }
+
+    fn new_line(&mut self) {
+        if self.compact() {
+            return;
+        }
+        self.content.push('\n');
+        self.content
+            .push_str(&" ".repeat(self.level * self.style.indent));
+    }
+
+    /// Keys, scalar values and comments, written with `\u` escapes when only ascii is written
+    fn push_json(&mut self, json: &str) {
+        match self.style.ascii {
+            true => self.content.push_str(&escape_non_ascii(json)),
+            false => self.content.push_str(json),
+        }
+    }
+
+    fn emit_node(&mut self, node: &Node) {
+        match &node.value {
+            NodeType::ARRAY(elements) => {
+                let elements: Vec<&Node> = elements.iter().collect();
+                self.emit_entries(&elements, false, ('[', ']'));
+            }
+            NodeType::NONE => {
+                let children: Vec<&Node> = node.children.iter().collect();
+                self.emit_entries(&children, true, ('{', '}'));
+            }
+            _ => self.push_json(&JsonValue::from(node).dump()),
+        }
+    }
+
+    /// Members of an object or elements of an array, trailing comments follow the comma
+    fn emit_entries(&mut self, entries: &[&Node], with_keys: bool, brackets: (char, char)) {
+        self.content.push(brackets.0);
+        if entries.is_empty() {
+            self.content.push(brackets.1);
+            return;
+        }
+        let comments = self.style.comments && !self.compact();
+        self.level += 1;
+        for (index, entry) in entries.iter().enumerate() {
+            self.new_line();
+            if comments {
+                for comment in &entry.comments {
+                    self.push_json(&comment_text(comment));
+                    self.new_line();
+                }
+            }
+            if with_keys {
+                self.push_json(&json::stringify(entry.name.as_str()));
+                self.content.push(':');
+                if !self.compact() {
+                    self.content.push(' ');
+                }
+            }
+            self.emit_node(entry);
+            if index < entries.len() - 1 {
+                self.content.push(',');
+            }
+            if let Some(comment) = entry.trailing_comment.as_ref().filter(|_| comments) {
+                self.content.push(' ');
+                self.push_json(&comment_text(comment));
+            }
+        }
+        self.level -= 1;
+        self.new_line();
+        self.content.push(brackets.1);
+    }
+}
+
+/// Nodes of a root list element, objects are written with their members
+fn document_nodes(element: &Node) -> Nodes {
+    let mut nodes = Nodes::new();
+    match &element.value {
+        NodeType::NONE if !element.children.is_empty() => {
+            for child in &element.children {
+                let mut member = child.clone();
+                member.parent = None;
+                nodes.push(member);
+            }
+        }
+        _ => {
+            let mut root = element.clone();
+            root.name = String::new();
+            root.parent = None;
+            nodes.push(root);
+        }
+    }
+    nodes
+}
+
+fn comment_text(comment: &str) -> String {
+    if comment.is_empty() {
+        return String::from("//");
+    }
+    ["// ", comment].concat()
+}
+
+/// Non-ASCII characters of dumped json only appear in strings, escapes are read back unchanged
+fn escape_non_ascii(content: &str) -> String {
+    let mut escaped = String::new();
+    for character in content.chars() {
+        if character.is_ascii() {
+            escaped.push(character);
+            continue;
+        }
+        let mut utf16 = [0; 2];
+        for unit in character.encode_utf16(&mut utf16) {
+            escaped.push_str(&format!("\\u{:04x}", unit));
+        }
+    }
+    escaped
+}
@@ -0,0 +1,204 @@
+#[cfg(test)]
+mod tests {
+    use crate::json_writer::{JsonStyle, JsonWriter};
+    use crate::nodes::Nodes;
+    use crate::nodes_writer::to_json_value;
+    use crate::yaml_file_reader::YamlFileReader;
+
+    fn parse(content: &str) -> Nodes {
+        let yaml_data: serde_yaml::Value = serde_yaml::from_str(content).unwrap();
+        YamlFileReader::convert_yaml_values_to_nodes(&yaml_data).unwrap()
+    }
+
+    #[test]
+    fn write_like_json_pretty() {
+        let nodes = parse(
+            "server:
+  port: 8080
+  host: \"a \\\"b\\\"\"
+  ratio: 0.5
+  empty: ~
+  enabled: true
+servers:
+  - name: a
+    ports: [80, 443]
+  - name: b
+    ports: []
+nested: {}
+",
+        );
+
+        assert_eq!(
+            to_json_value(&nodes).pretty(1),
+            JsonWriter::write(&nodes, &JsonStyle::default())
+        );
+    }
+
+    #[test]
+    fn write_root_list() {
+        let nodes = parse("- a\n- b\n");
+
+        assert_eq!(
+            to_json_value(&nodes).pretty(1),
+            JsonWriter::write(&nodes, &JsonStyle::default())
+        );
+    }
+
+    #[test]
+    fn write_comments() {
+        let mut nodes = parse("server:\n  port: 8080\n  host: localhost\n");
+        nodes.find_mut("server.port").unwrap().comments = vec!["the port".to_string()];
+        nodes.find_mut("server.port").unwrap().trailing_comment = Some("default".to_string());
+        nodes.find_mut("server.host").unwrap().trailing_comment = Some("local".to_string());
+        let comments = JsonStyle {
+            comments: true,
+            ..JsonStyle::default()
+        };
+
+        assert_eq!(
+            "{
+ \"server\": {
+  // the port
+  \"port\": 8080, // default
+  \"host\": \"localhost\" // local
+ }
+}",
+            JsonWriter::write(&nodes, &comments)
+        );
+    }
+
+    #[test]
+    fn write_compact_and_indent() {
+        let nodes = parse("server:\n  port: 8080\n  hosts: [a, b]\n  empty: []\n");
+
+        let compact = JsonStyle {
+            compact: true,
+            ..JsonStyle::default()
+        };
+        assert_eq!(
+            to_json_value(&nodes).dump(),
+            JsonWriter::write(&nodes, &compact)
+        );
+
+        let indent = JsonStyle {
+            indent: 4,
+            ..JsonStyle::default()
+        };
+        assert_eq!(
+            to_json_value(&nodes).pretty(4),
+            JsonWriter::write(&nodes, &indent)
+        );
+    }
+
+    #[test]
+    fn write_sorted_keys() {
+        let nodes = parse("b: 1\na:\n  z: [3, 1]\n  y: true\n");
+        let style = JsonStyle {
+            compact: true,
+            sort_keys: true,
+            ..JsonStyle::default()
+        };
+
+        assert_eq!(
+            "{\"a\":{\"y\":true,\"z\":[3,1]},\"b\":1}",
+            JsonWriter::write(&nodes, &style)
+        );
+    }
+
+    #[test]
+    fn write_ascii() {
+        let nodes = parse("greeting: Grüße 😀\n");
+        let style = JsonStyle {
+            ascii: true,
+            ..JsonStyle::default()
+        };
+
+        let written = JsonWriter::write(&nodes, &style);
+        assert_eq!(
+            "{\n \"greeting\": \"Gr\\u00fc\\u00dfe \\ud83d\\ude00\"\n}",
+            written
+        );
+        assert_eq!(
+            "Grüße 😀",
+            json::parse(&written).unwrap()["greeting"].as_str().unwrap()
+        );
+    }
+
+    #[test]
+    fn write_ascii_escapes_comments() {
+        let mut nodes = parse("größe: 1\n");
+        nodes.find_mut("größe").unwrap().comments = vec!["Größe in cm".to_string()];
+        let style = JsonStyle {
+            ascii: true,
+            comments: true,
+            ..JsonStyle::default()
+        };
+
+        assert_eq!(
+            "{\n // Gr\\u00f6\\u00dfe in cm\n \"gr\\u00f6\\u00dfe\": 1\n}",
+            JsonWriter::write(&nodes, &style)
+        );
+    }
+
+    #[test]
+    fn write_flat_sorted_indices() {
+        let nodes = parse("servers: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]\nname: demo\n");
+        let style = JsonStyle {
+            flat: true,
+            sort_keys: true,
+            compact: true,
+            ..JsonStyle::default()
+        };
+
+        let written = JsonWriter::write(&nodes, &style);
+        assert!(written.starts_with("{\"name\":\"demo\",\"servers[0]\":0,\"servers[1]\":1,"));
+        assert!(written.ends_with("\"servers[9]\":9,\"servers[10]\":10}"));
+    }
+
+    #[test]
+    fn write_flat() {
+        let nodes = parse("server:\n  port: 8080\n  hosts: [a, b]\n  tls: {}\nname: demo\n");
+        let style = JsonStyle {
+            flat: true,
+            sort_keys: true,
+            compact: true,
+            ..JsonStyle::default()
+        };
+
+        assert_eq!(
+            "{\"name\":\"demo\",\"server.hosts[0]\":\"a\",\"server.hosts[1]\":\"b\",\
+            \"server.port\":8080,\"server.tls\":{}}",
+            JsonWriter::write(&nodes, &style)
+        );
+    }
+
+    #[test]
+    fn write_ndjson() {
+        let nodes = parse("- name: a\n  port: 80\n- name: b\n  tags: [x]\n- plain\n");
+        let style = JsonStyle {
+            ndjson: true,
+            ..JsonStyle::default()
+        };
+        assert_eq!(
+            "{\"name\":\"a\",\"port\":80}\n{\"name\":\"b\",\"tags\":[\"x\"]}\n\"plain\"",
+            JsonWriter::write(&nodes, &style)
+        );
+
+        let flat = JsonStyle {
+            ndjson: true,
+            flat: true,
+            ..JsonStyle::default()
+        };
+        assert_eq!(
+            "{\"name\":\"a\",\"port\":80}\n{\"name\":\"b\",\"tags[0]\":\"x\"}\n\"plain\"",
+            JsonWriter::write(&nodes, &flat)
+        );
+
+        // other files are a single document
+        let nodes = parse("server:\n  port: 8080\n");
+        assert_eq!(
+            "{\"server\":{\"port\":8080}}",
+            JsonWriter::write(&nodes, &style)
+        );
+    }
+}
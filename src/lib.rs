@@ -1,31 +1,53 @@
 use std::fs::File;
 use std::io::{stdin, BufReader, Read};
-use std::path::PathBuf;
+use std::path::Path;
 
 use clap::Parser;
+use exitcode::ExitCode;
 use is_terminal::IsTerminal as _;
+use linked_hash_map::LinkedHashMap;
 use log::{debug, info};
 
 use crate::args::{Args, TargetFormat};
+use crate::dotenv_file_reader::DotenvFileReader;
 use crate::errors::ProconError;
+use crate::interpolation::interpolate;
 use crate::json_file_reader::JsonFileReader;
+use crate::node::NodeType;
 use crate::nodes::Nodes;
-use crate::nodes_writer::{to_json, to_properties, to_yaml};
+use crate::nodes_diff::diff;
+use crate::nodes_writer::{
+    determine_output_filename, to_config_map, to_dotenv, to_json, to_properties, to_toml, to_yaml,
+};
 use crate::property_file_reader::PropertyFileReader;
+use crate::query::Query;
+use crate::toml_file_reader::TomlFileReader;
 use crate::yaml_file_reader::YamlFileReader;
 
 pub mod args;
+pub mod config_map;
+pub mod dotenv_file_reader;
 pub mod errors;
+pub mod interpolation;
 pub mod json_file_reader;
+pub mod json_writer;
 pub mod line;
 pub mod node;
 pub mod nodes;
+pub mod nodes_diff;
+pub mod nodes_validator;
 pub mod nodes_writer;
 pub mod nodes_writer_test;
 pub mod property_file_reader;
+pub mod query;
+pub mod toml_file_reader;
 pub mod yaml_file_reader;
+pub mod yaml_writer;
 
-pub fn run() -> Result<String, ProconError> {
+/// Exit code of the diff command if the files differ, like diff(1)
+pub const FILES_DIFFER: ExitCode = 1;
+
+pub fn run() -> Result<(String, ExitCode), ProconError> {
     let args: Args = parse_args_and_setup_logger()?;
 
     env_logger::Builder::new()
@@ -37,8 +59,35 @@ pub fn run() -> Result<String, ProconError> {
         debug!("User: terminal");
     }
 
-    let nodes = parse_input_file(&args)?;
-    convert_nodes(&args, &nodes)
+    if let TargetFormat::Diff {
+        json, other_file, ..
+    } = &args.target_format
+    {
+        return diff_files(&args, *json, other_file);
+    }
+    if let TargetFormat::Validate { schema, .. } = &args.target_format {
+        return validate_file(&args, schema);
+    }
+    if let TargetFormat::Get { key, .. } = &args.target_format {
+        return get_value(&args, key);
+    }
+    if let TargetFormat::Set { assignment, .. } = &args.target_format {
+        return set_value(&args, assignment);
+    }
+
+    if args.split {
+        return split_documents(&args);
+    }
+
+    let nodes = select_nodes(&args, parse_input_file(&args)?)?;
+    Ok((convert_nodes(&args, &nodes)?, exitcode::OK))
+}
+
+fn select_nodes(args: &Args, nodes: Nodes) -> Result<Nodes, ProconError> {
+    match &args.select {
+        Some(expression) => Ok(expression.parse::<Query>()?.select(&nodes)),
+        None => Ok(nodes),
+    }
 }
 
 fn parse_args_and_setup_logger() -> Result<Args, ProconError> {
@@ -49,67 +98,253 @@ fn parse_args_and_setup_logger() -> Result<Args, ProconError> {
 
 pub fn parse_input_file(args: &Args) -> Result<Nodes, ProconError> {
     debug!("\n####################################\nLoad property files\n####################################");
-    let content: String = read_file_or_stdin(&args)?;
-    return if args.target_format.path_buf() == &PathBuf::from("-") {
-        try_reader_from_flag_or_all_sequential(&args, &content)
+    let mut nodes = parse_file(args, args.target_format.path_buf())?;
+    // layered configuration, later files override earlier ones
+    for merge_file in args.target_format.merge_files() {
+        let merge_nodes = parse_file(args, merge_file)?;
+        nodes.override_with(&merge_nodes, &args.array_merge);
+    }
+    if args.interpolate {
+        interpolate(&mut nodes)?;
+    }
+    Ok(nodes)
+}
+
+fn parse_file(args: &Args, path: &Path) -> Result<Nodes, ProconError> {
+    let content: String = read_file_or_stdin(args, path)?;
+    let mut nodes = if path == Path::new("-") {
+        try_reader_from_flag_or_all_sequential(args, &content)
     } else {
-        find_parser_via_extension(&args, &content)
+        find_parser_via_extension(args, path, &content)
+    }?;
+    nodes.treat_as_strings(&args.string_keys, args.strict_numbers);
+    Ok(nodes)
+}
+
+/// Converts every document of a multi-document yaml file into its own numbered file
+fn split_documents(args: &Args) -> Result<(String, ExitCode), ProconError> {
+    let path = args.target_format.path_buf();
+    let yaml_input = match path.to_str() {
+        Some("-") => args.from_yaml_file,
+        _ => matches!(file_extension(path).as_str(), "yml" | "yaml"),
     };
+    if !yaml_input || !args.target_format.merge_files().is_empty() {
+        return Err(ProconError {
+            message: "Split needs a single yaml input file".to_string(),
+        });
+    }
+
+    let content = read_file_or_stdin(args, path)?;
+    let output_filename = determine_output_filename(args);
+    let mut messages: Vec<String> = vec![];
+    for (index, mut nodes) in YamlFileReader::parse_documents(&content)?
+        .into_iter()
+        .enumerate()
+    {
+        nodes.treat_as_strings(&args.string_keys, args.strict_numbers);
+        if args.interpolate {
+            interpolate(&mut nodes)?;
+        }
+        let mut document_args = args.clone();
+        document_args.output_filename = Some(numbered_filename(&output_filename, index));
+        let nodes = select_nodes(&document_args, nodes)?;
+        messages.push(convert_nodes(&document_args, &nodes)?);
+    }
+    Ok((messages.join("\n"), exitcode::OK))
+}
+
+/// Adds the index before the extension like application-0.json
+fn numbered_filename(filename: &str, index: usize) -> String {
+    let path = Path::new(filename);
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default();
+    let index = index.to_string();
+    let numbered = match path.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) => [stem, "-", &index, ".", extension].concat(),
+        None => [stem, "-", &index].concat(),
+    };
+    path.with_file_name(numbered).to_str().unwrap().to_string()
 }
 
-fn read_file_or_stdin(args: &Args) -> Result<String, ProconError> {
-    let mut content = String::new();
-    let count;
+fn diff_files(
+    args: &Args,
+    json: bool,
+    other_file: &Path,
+) -> Result<(String, ExitCode), ProconError> {
+    let nodes = parse_input_file(args)?;
+    let other_nodes = parse_file(args, other_file)?;
 
-    // todo I guess this should work with generics somehow
-    let path_buf = args.target_format.path_buf();
-    if path_buf == &PathBuf::from("-") {
+    let differences = diff(&nodes, &other_nodes);
+    if json {
+        println!("{}", nodes_diff::to_json(&differences));
+    } else {
+        print!("{}", nodes_diff::to_human(&differences));
+    }
+
+    if differences.is_empty() {
+        Ok((String::from("Files are equal"), exitcode::OK))
+    } else {
+        Ok((String::from("Files differ"), FILES_DIFFER))
+    }
+}
+
+fn read_file_or_stdin(args: &Args, path: &Path) -> Result<String, ProconError> {
+    let mut bytes: Vec<u8> = Vec::new();
+
+    let count = if path == Path::new("-") {
         if stdin().is_terminal() {
             return Err(ProconError {
                 message: "Nothing piped into stdin".to_string(),
             });
         }
         let mut buffer = BufReader::new(stdin().lock());
-        count = buffer.read_to_string(&mut content);
+        buffer.read_to_end(&mut bytes)
     } else {
-        let file = File::open(&path_buf).map_err(|_| ProconError {
+        let file = File::open(path).map_err(|_| ProconError {
             message: "Unable to read file".to_string(),
         })?;
         let mut buffer = BufReader::new(file);
-        count = buffer.read_to_string(&mut content);
-    }
+        buffer.read_to_end(&mut bytes)
+    };
 
     debug!("Read {:?} bytes", count);
-    Ok(content)
+    let properties = match path.to_str() {
+        Some("-") => args.from_property_file,
+        _ => file_extension(path) == "properties",
+    };
+    decode(bytes, properties)
+}
+
+/// UTF-8 content, properties files with anything else are read as ISO-8859-1 like
+/// `java.util.Properties` does
+fn decode(bytes: Vec<u8>, properties: bool) -> Result<String, ProconError> {
+    String::from_utf8(bytes).or_else(|error| match properties {
+        true => {
+            debug!("No UTF-8 content, read as ISO-8859-1");
+            Ok(error
+                .into_bytes()
+                .iter()
+                .map(|byte| char::from(*byte))
+                .collect())
+        }
+        false => Err(ProconError {
+            message: "File is not valid UTF-8".to_string(),
+        }),
+    })
+}
+
+fn validate_file(args: &Args, schema: &Path) -> Result<(String, ExitCode), ProconError> {
+    let nodes = parse_input_file(args)?;
+    let schema_content = read_file_or_stdin(args, schema)?;
+
+    let violations =
+        nodes_validator::validate(&nodes, &schema_content, &source_line_numbers(args))?;
+    for violation in &violations {
+        println!("{}", violation);
+    }
+
+    if violations.is_empty() {
+        Ok((String::from("File is valid"), exitcode::OK))
+    } else {
+        Ok((String::from("File is invalid"), exitcode::DATAERR))
+    }
+}
+
+/// Line numbers by dotted key, only properties and yaml files keep track of them
+fn source_line_numbers(args: &Args) -> LinkedHashMap<String, u32> {
+    let path = args.target_format.path_buf();
+    let Ok(content) = read_file_or_stdin(args, path) else {
+        return LinkedHashMap::new();
+    };
+    match file_extension(path).as_str() {
+        "properties" => PropertyFileReader::line_numbers(args, &content),
+        "yml" | "yaml" => YamlFileReader::line_numbers(&content),
+        &_ => LinkedHashMap::new(),
+    }
+}
+
+fn get_value(args: &Args, key: &str) -> Result<(String, ExitCode), ProconError> {
+    let nodes = parse_input_file(args)?;
+    let node = nodes.find(key).ok_or(ProconError {
+        message: ["Key not found: ", key].concat(),
+    })?;
+
+    match &node.value {
+        // objects and arrays of objects are printed with all their nested keys
+        NodeType::NONE => print!("{}", node.to_properties_at(key)),
+        NodeType::ARRAY(elements) if !elements.iter().all(|element| element.value.is_scalar()) => {
+            print!("{}", node.to_properties_at(key))
+        }
+        value => println!("{}", value),
+    }
+    Ok((["Read ", key].concat(), exitcode::OK))
+}
+
+fn set_value(args: &Args, assignment: &str) -> Result<(String, ExitCode), ProconError> {
+    let (key, value) = assignment.split_once('=').ok_or(ProconError {
+        message: "Expected KEY=VALUE".to_string(),
+    })?;
+    // the file keeps its placeholders, resolved values are never written back
+    let args = &Args {
+        interpolate: false,
+        ..args.clone()
+    };
+    let mut nodes = parse_input_file(args)?;
+    nodes.set(key, value)?;
+
+    // write back in the format of the input file
+    let message = match file_extension(args.target_format.path_buf()).as_str() {
+        "properties" => to_properties(args, &nodes),
+        "yml" | "yaml" => to_yaml(args, &nodes),
+        "json" => to_json(args, &nodes),
+        "toml" => to_toml(args, &nodes),
+        "env" => to_dotenv(args, &nodes),
+        &_ => Err(ProconError {
+            message: "Set needs a *.properties, *.json, *.yaml, *.toml or *.env file".to_string(),
+        }),
+    }?;
+    Ok((message, exitcode::OK))
 }
 
 fn try_reader_from_flag_or_all_sequential(
     args: &Args,
-    content: &String,
+    content: &str,
 ) -> Result<Nodes, ProconError> {
     if args.from_property_file {
-        return PropertyFileReader::parse(&args, &content);
+        return PropertyFileReader::parse(args, content);
     }
     if args.from_json_file {
-        return JsonFileReader::parse(&args, &content);
+        return JsonFileReader::parse(args, content);
     }
     if args.from_yaml_file {
-        return YamlFileReader::parse(&args, &content);
+        return YamlFileReader::parse(args, content);
+    }
+    if args.from_toml_file {
+        return TomlFileReader::parse(args, content);
     }
-    try_all_readers(&args, &content)
+    if args.from_dotenv_file {
+        return DotenvFileReader::parse(args, content);
+    }
+    try_all_readers(args, content)
 }
 
-fn try_all_readers(args: &Args, content: &String) -> Result<Nodes, ProconError> {
+fn try_all_readers(args: &Args, content: &str) -> Result<Nodes, ProconError> {
     info!("Guess input file");
-    let json_nodes = JsonFileReader::parse(&args, &content);
+    let json_nodes = JsonFileReader::parse(args, content);
     if json_nodes.is_ok() {
         return json_nodes;
     }
-    let yaml_nodes = YamlFileReader::parse(&args, &content);
+    let toml_nodes = TomlFileReader::parse(args, content);
+    if toml_nodes.is_ok() {
+        return toml_nodes;
+    }
+    let yaml_nodes = YamlFileReader::parse(args, content);
     if yaml_nodes.is_ok() {
         return yaml_nodes;
     }
-    let result_nodes = PropertyFileReader::parse(&args, &content);
+    let result_nodes = PropertyFileReader::parse(args, content);
     if result_nodes.is_ok() {
         return result_nodes;
     }
@@ -117,43 +352,76 @@ fn try_all_readers(args: &Args, content: &String) -> Result<Nodes, ProconError>
     Ok(Nodes::new())
 }
 
-fn find_parser_via_extension(args: &Args, content: &String) -> Result<Nodes, ProconError> {
-    let extension: &str = &args
-        .target_format
-        .path_buf()
-        .extension()
-        .unwrap()
-        .to_str()
-        .unwrap();
-
-    let nodes = match extension.to_lowercase().as_str() {
-        "properties" => PropertyFileReader::parse(&args, &content),
-        "yml" => YamlFileReader::parse(&args, &content),
-        "yaml" => YamlFileReader::parse(&args, &content),
-        "json" => JsonFileReader::parse(&args, &content),
+fn find_parser_via_extension(
+    args: &Args,
+    path: &Path,
+    content: &str,
+) -> Result<Nodes, ProconError> {
+    let nodes = match file_extension(path).as_str() {
+        "properties" => PropertyFileReader::parse(args, content),
+        "yml" => YamlFileReader::parse(args, content),
+        "yaml" => YamlFileReader::parse(args, content),
+        "json" => JsonFileReader::parse(args, content),
+        "toml" => TomlFileReader::parse(args, content),
+        "env" => DotenvFileReader::parse(args, content),
         &_ => Err(ProconError {
-            message: "Not supported file type:\n\t*.properties\n\t*.json\n\t*.yaml".to_string(),
+            message:
+                "Not supported file type:\n\t*.properties\n\t*.json\n\t*.yaml\n\t*.toml\n\t*.env"
+                    .to_string(),
         }),
     }?;
 
-    info!("Read {}", &args.target_format.path_buf().to_str().unwrap());
+    info!("Read {}", path.to_str().unwrap());
     Ok(nodes)
 }
 
+/// Lowercase extension of a file, dotenv files like `.env` or `.env.local` count as `env`
+fn file_extension(path: &Path) -> String {
+    let file_name = path
+        .file_name()
+        .and_then(|file_name| file_name.to_str())
+        .unwrap_or_default();
+    if file_name == ".env" || file_name.starts_with(".env.") {
+        return "env".to_string();
+    }
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
 fn convert_nodes(args: &Args, nodes: &Nodes) -> Result<String, ProconError> {
     debug!("\n####################################\nStart format conversion\n####################################");
     match args.target_format {
         TargetFormat::Properties { .. } => {
             debug!("Convert to properties");
-            to_properties(&args, &nodes)
+            to_properties(args, nodes)
         }
         TargetFormat::Json { .. } => {
             debug!("Convert to json");
-            to_json(&args, &nodes)
+            to_json(args, nodes)
         }
         TargetFormat::Yaml { .. } => {
             debug!("Convert to yaml");
-            to_yaml(&args, &nodes)
+            to_yaml(args, nodes)
         }
+        TargetFormat::Toml { .. } => {
+            debug!("Convert to toml");
+            to_toml(args, nodes)
+        }
+        TargetFormat::Dotenv { .. } => {
+            debug!("Convert to dotenv");
+            to_dotenv(args, nodes)
+        }
+        TargetFormat::ConfigMap { .. } => {
+            debug!("Convert to configmap");
+            to_config_map(args, nodes)
+        }
+        TargetFormat::Diff { .. }
+        | TargetFormat::Get { .. }
+        | TargetFormat::Set { .. }
+        | TargetFormat::Validate { .. } => Err(ProconError {
+            message: "Nothing to convert".to_string(),
+        }),
     }
 }
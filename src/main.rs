@@ -8,10 +8,11 @@ use std::process;
 use procon::run;
 
 fn main() {
-    let message = run().unwrap_or_else(|err| {
+    let (message, exit_code) = run().unwrap_or_else(|err| {
         error!("{}", err.to_string());
         Args::command().print_help().unwrap();
         process::exit(exitcode::CONFIG);
     });
     info!("{}", message);
+    process::exit(exit_code);
 }
@@ -1,7 +1,7 @@
 extern crate exitcode;
 
 use std::cmp::Ordering;
-use std::fmt::Debug;
+use std::fmt::{Debug, Display, Formatter};
 use std::rc::Rc;
 use std::str::FromStr;
 
@@ -10,77 +10,175 @@ use linked_hash_map::LinkedHashMap;
 use log::{debug, trace};
 use yaml_rust::Yaml;
 
+use crate::nodes::{ArrayMerge, DelimiterSpacing};
+use crate::property_file_reader::{Delimiter, KeySegment, PropertyFileReader};
+
 #[cfg(test)]
 #[path = "./node_test.rs"]
 mod node_test;
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Clone, PartialEq)]
 pub enum NodeType {
     BOOLEAN(bool),
     NUMERIC(String),
     STRING(String),
     OBJECT(String),
-    ARRAY(Vec<String>),
+    ARRAY(Vec<Node>),
+    /// explicit null value like `null` in json or `~` in yaml
+    NULL,
+    /// intermediate node which only holds children
     NONE,
 }
 
 impl NodeType {
     pub fn parse(value: &str) -> NodeType {
-        if value.to_lowercase() == "true" || value.to_lowercase() == "false" {
-            return NodeType::BOOLEAN(FromStr::from_str(&value.to_lowercase()).unwrap());
-        }
-
-        if value.to_lowercase().starts_with("{") {
-            return NodeType::OBJECT(value.to_string());
-        }
-
         let parts: Vec<&str> = value.split(',').collect();
-        if parts.len() > 1 {
-            let mut array: Vec<String> = vec![];
+        if parts.len() > 1 && !value.starts_with('{') {
+            let mut array: Vec<Node> = vec![];
             for value in parts.iter() {
                 // trailing commas will result in empty string
-                if value.len() > 0 {
-                    array.push(value.to_string())
+                if !value.is_empty() {
+                    let element_value = NodeType::STRING(value.to_string());
+                    array.push(Node::new_array_element(array.len(), element_value));
                 }
             }
             return NodeType::ARRAY(array);
         }
+        Self::parse_scalar(value)
+    }
 
-        let mut is_numeric = match value.parse::<f64>() {
-            Ok(_) => true,
-            Err(_) => false,
-        };
-        if !is_numeric {
-            is_numeric = match value.parse::<usize>() {
-                Ok(_) => true,
-                Err(_) => false,
-            };
+    /// Single value like `parse`, commas are part of the value
+    pub fn parse_scalar(value: &str) -> NodeType {
+        if value.to_lowercase() == "true" || value.to_lowercase() == "false" {
+            return NodeType::BOOLEAN(FromStr::from_str(&value.to_lowercase()).unwrap());
         }
-        if is_numeric {
-            return NodeType::NUMERIC(value.parse().unwrap());
+
+        if value.to_lowercase().starts_with("{") {
+            return NodeType::OBJECT(value.to_string());
+        }
+
+        if is_plain_number(value) {
+            return NodeType::NUMERIC(value.to_string());
         }
         NodeType::STRING(value.to_string())
     }
 
-    pub fn to_string(&self) -> String {
+    /// Numbers are canonical if every consumer reads them back with the same lexeme, which rules
+    /// out trailing zeros like `1.10` and integers beyond the precision of a double like 64 bit ids
+    pub fn is_canonical_number(&self) -> bool {
+        match self {
+            NodeType::NUMERIC(value) => value
+                .parse::<f64>()
+                .map(|number| number.is_finite() && number.to_string() == *value)
+                .unwrap_or(false),
+            _ => false,
+        }
+    }
+
+    /// Scalar values can be written as a single property value
+    pub fn is_scalar(&self) -> bool {
+        !matches!(self, NodeType::ARRAY(_) | NodeType::NONE)
+    }
+}
+
+impl Display for NodeType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match &self {
-            NodeType::STRING(value) => value.clone(),
-            NodeType::NUMERIC(value) => value.clone(),
-            NodeType::BOOLEAN(value) => value.to_string(),
-            NodeType::OBJECT(value) => value.clone(),
+            NodeType::STRING(value) => write!(f, "{}", value),
+            NodeType::NUMERIC(value) => write!(f, "{}", value),
+            NodeType::BOOLEAN(value) => write!(f, "{}", value),
+            NodeType::OBJECT(value) => write!(f, "{}", value),
             NodeType::ARRAY(array) => {
-                let mut formatted_string: String = "".to_string();
-                for (index, value) in array.iter().enumerate() {
-                    if index != 0 {
-                        formatted_string.push_str(",");
-                    }
-                    formatted_string.push_str(value.as_str());
-                }
-                formatted_string
+                let values: Vec<String> = array.iter().map(|node| node.value.to_string()).collect();
+                write!(f, "{}", values.join(","))
             }
-            NodeType::NONE => "".to_string(),
+            NodeType::NULL => write!(f, ""),
+            NodeType::NONE => write!(f, ""),
+        }
+    }
+}
+
+/// Plain decimal numbers like `-12` or `1.50`, leading zeros like `007`, exponents like `1e5`
+/// or special values like `NaN` are no numbers in untyped input
+fn is_plain_number(value: &str) -> bool {
+    let unsigned = value.strip_prefix('-').unwrap_or(value);
+    let (integer, fraction) = match unsigned.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (unsigned, None),
+    };
+    let is_digits = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit());
+    if !is_digits(integer) || (integer.len() > 1 && integer.starts_with('0')) {
+        return false;
+    }
+    fraction.map(is_digits).unwrap_or(true)
+}
+
+/// Plain numbers with an optional exponent like `1.10` or `-2e5`, which every format can hold
+pub(crate) fn is_decimal_number(value: &str) -> bool {
+    match value.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => {
+            let digits = exponent.strip_prefix(['-', '+']).unwrap_or(exponent);
+            is_plain_number(mantissa)
+                && !digits.is_empty()
+                && digits.chars().all(|c| c.is_ascii_digit())
         }
+        None => is_plain_number(value),
+    }
+}
+
+/// Sign, significant digits and exponent of a number lexeme like `-1.50e3`
+///
+/// Used to check if a target format can hold a number without changing its value
+fn decimal_parts(lexeme: &str) -> Option<(bool, String, i64)> {
+    let (negative, unsigned) = match lexeme.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, lexeme.strip_prefix('+').unwrap_or(lexeme)),
+    };
+    let (mantissa, mut exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, exponent.parse::<i64>().ok()?),
+        None => (unsigned, 0),
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = [integer, fraction].concat();
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    exponent -= fraction.len() as i64;
+    let significant = digits.trim_start_matches('0');
+    let trimmed = significant.trim_end_matches('0');
+    if trimmed.is_empty() {
+        return Some((false, String::new(), 0));
     }
+    exponent += (significant.len() - trimmed.len()) as i64;
+    Some((negative, trimmed.to_string(), exponent))
+}
+
+pub(crate) fn is_same_number(lexeme: &str, other_lexeme: &str) -> bool {
+    let parts = decimal_parts(lexeme);
+    parts.is_some() && parts == decimal_parts(other_lexeme)
+}
+
+/// Options of the properties writer
+#[derive(Debug, Clone, Default)]
+pub struct PropertiesStyle {
+    /// every array element gets its own `key[index]=value` line
+    pub indexed_arrays: bool,
+    /// non-ASCII characters are written as `\uXXXX` for ISO-8859-1 consumers
+    pub escape_unicode: bool,
+    /// longer lines are continued on the next line with a `\`
+    pub line_width: Option<usize>,
+    /// delimiter between key and value
+    pub delimiter: Delimiter,
+    /// spaces around the delimiter, not used for the whitespace delimiter
+    pub delimiter_spacing: DelimiterSpacing,
+    /// keys are sorted alphabetically, array elements keep their order
+    pub sort_keys: bool,
+    /// top-level groups are separated by a blank line
+    pub separate_groups: bool,
+    /// comment written at the top of the file
+    pub header: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -90,6 +188,12 @@ pub struct Node {
     pub children: Vec<Node>,
     pub name: String,
     pub value: NodeType,
+    /// yaml tag like `!Ref` or `!!binary`, the value holds the tagged value
+    pub tag: Option<String>,
+    /// comment lines above the key without their `#`
+    pub comments: Vec<String>,
+    /// comment at the end of the line of the key
+    pub trailing_comment: Option<String>,
 }
 
 impl Node {
@@ -106,6 +210,9 @@ impl Node {
             children,
             name,
             value,
+            tag: None,
+            comments: Vec::new(),
+            trailing_comment: None,
         };
         debug!("Create new node {:?}", node);
         node
@@ -119,6 +226,11 @@ impl Node {
         Self::new_from_name_and_level(0, name)
     }
 
+    /// Elements of an array are named by their index
+    pub fn new_array_element(index: usize, value: NodeType) -> Node {
+        Self::new(0, None, Vec::new(), index.to_string(), value)
+    }
+
     pub fn new_child(level: usize, parent: &mut Node, name: &str) -> Node {
         Self::new(
             level,
@@ -143,29 +255,229 @@ impl Node {
 
         // case same node
         if self == new_node {
-            return self.find_common_node(&new_node.children[0]);
+            self.merge_children(new_node);
+            return false;
         }
 
         for existing_node in &mut self.children {
             if existing_node == new_node {
-                return existing_node.find_common_node(&new_node.children[0]);
+                existing_node.merge_children(new_node);
+                return false;
             }
         }
 
         trace!("Merge {:?} into {:?}", new_node, self);
         let children = &mut self.children;
         children.push(new_node.to_owned());
-        return false;
+        false
+    }
+
+    /// Merges the children and array elements of the same node into this node
+    fn merge_children(&mut self, new_node: &Node) {
+        for new_child in &new_node.children {
+            match self.children.iter_mut().find(|child| *child == new_child) {
+                Some(existing_child) => existing_child.merge_children(new_child),
+                None => self.children.push(new_child.to_owned()),
+            }
+        }
+
+        // array elements are merged by their index
+        if let (NodeType::ARRAY(elements), NodeType::ARRAY(new_elements)) =
+            (&mut self.value, &new_node.value)
+        {
+            for new_element in new_elements {
+                match elements
+                    .iter_mut()
+                    .find(|element| element.name == new_element.name)
+                {
+                    Some(existing_element) => existing_element.merge_children(new_element),
+                    None => elements.push(new_element.to_owned()),
+                }
+            }
+            elements.sort_by_key(|element| element.name.parse::<usize>().unwrap_or(usize::MAX));
+        }
+    }
+
+    /// Deep merges the same node of another file into this node, values of the other node win
+    pub fn override_with(&mut self, other: &Node, array_merge: &ArrayMerge) {
+        if !other.comments.is_empty() {
+            self.comments = other.comments.clone();
+        }
+        if other.trailing_comment.is_some() {
+            self.trailing_comment = other.trailing_comment.clone();
+        }
+        match (&mut self.value, &other.value) {
+            (NodeType::ARRAY(elements), NodeType::ARRAY(other_elements)) => match array_merge {
+                ArrayMerge::Replace => self.value = other.value.clone(),
+                ArrayMerge::Append => {
+                    for other_element in other_elements {
+                        let mut element = other_element.to_owned();
+                        element.name = elements.len().to_string();
+                        elements.push(element);
+                    }
+                }
+                ArrayMerge::Index => {
+                    for other_element in other_elements {
+                        match elements
+                            .iter_mut()
+                            .find(|element| element.name == other_element.name)
+                        {
+                            Some(element) => element.override_with(other_element, array_merge),
+                            None => elements.push(other_element.to_owned()),
+                        }
+                    }
+                }
+            },
+            // objects are merged child by child
+            (NodeType::NONE, NodeType::NONE) => {
+                for other_child in &other.children {
+                    match self
+                        .children
+                        .iter_mut()
+                        .find(|child| child.name == other_child.name)
+                    {
+                        Some(child) => child.override_with(other_child, array_merge),
+                        None => self.children.push(other_child.to_owned()),
+                    }
+                }
+            }
+            // different types or scalars, the other node wins
+            _ => {
+                self.value = other.value.clone();
+                self.children = other.children.clone();
+                self.tag = other.tag.clone();
+            }
+        }
+    }
+
+    /// Nested node of the given key segments like the `port` node of `server.port`
+    pub(crate) fn find(&self, key_segments: &[KeySegment]) -> Option<&Node> {
+        match key_segments.first() {
+            None => Some(self),
+            Some(KeySegment::Name(name)) => self
+                .children
+                .iter()
+                .find(|child| child.name == *name)?
+                .find(&key_segments[1..]),
+            Some(KeySegment::Index(index)) => match &self.value {
+                NodeType::ARRAY(elements) => elements
+                    .iter()
+                    .find(|element| element.name == index.to_string())?
+                    .find(&key_segments[1..]),
+                _ => None,
+            },
+        }
+    }
+
+    pub(crate) fn find_mut(&mut self, key_segments: &[KeySegment]) -> Option<&mut Node> {
+        match key_segments.first() {
+            None => Some(self),
+            Some(KeySegment::Name(name)) => self
+                .children
+                .iter_mut()
+                .find(|child| child.name == *name)?
+                .find_mut(&key_segments[1..]),
+            Some(KeySegment::Index(index)) => match &mut self.value {
+                NodeType::ARRAY(elements) => elements
+                    .iter_mut()
+                    .find(|element| element.name == index.to_string())?
+                    .find_mut(&key_segments[1..]),
+                _ => None,
+            },
+        }
     }
 
     pub fn sort(&mut self) {
         for node in &mut self.children {
-            if !node.children.is_empty() {
+            if !node.children.is_empty() || matches!(node.value, NodeType::ARRAY(_)) {
                 node.sort();
             }
         }
+        // the order of array elements is part of the value itself
+        if let NodeType::ARRAY(elements) = &mut self.value {
+            for element in elements {
+                element.sort();
+            }
+        }
         self.children.sort();
     }
+
+    /// Turns the values of the given keys into strings, with `strict_numbers` also every number
+    /// which is not canonical like `1.10`, `007` or a 64 bit id
+    pub fn treat_as_strings(&mut self, key: &str, string_keys: &[String], strict_numbers: bool) {
+        if string_keys.iter().any(|string_key| string_key == key) {
+            self.stringify_values();
+            return;
+        }
+        if strict_numbers
+            && matches!(self.value, NodeType::NUMERIC(_))
+            && !self.value.is_canonical_number()
+        {
+            self.value = NodeType::STRING(self.value.to_string());
+        }
+
+        if let NodeType::ARRAY(elements) = &mut self.value {
+            for (index, element) in elements.iter_mut().enumerate() {
+                let element_key = [key, "[", &index.to_string(), "]"].concat();
+                element.treat_as_strings(&element_key, string_keys, strict_numbers);
+            }
+        }
+        for child in &mut self.children {
+            let child_key = child_key(key, &child.name);
+            child.treat_as_strings(&child_key, string_keys, strict_numbers);
+        }
+    }
+
+    fn stringify_values(&mut self) {
+        match &mut self.value {
+            NodeType::ARRAY(elements) => {
+                for element in elements {
+                    element.stringify_values();
+                }
+            }
+            NodeType::NULL | NodeType::NONE => {}
+            value => *value = NodeType::STRING(value.to_string()),
+        }
+        for child in &mut self.children {
+            child.stringify_values();
+        }
+    }
+
+    /// Properties representation of the node and its children
+    ///
+    /// Arrays of scalars are comma separated unless `indexed_arrays` is set, then every element
+    /// gets its own `key[index]=value` line as used by Spring
+    pub fn to_properties(&self, indexed_arrays: bool) -> String {
+        self.to_properties_with_style(&PropertiesStyle {
+            indexed_arrays,
+            ..PropertiesStyle::default()
+        })
+    }
+
+    /// Properties representation with escaped keys and values as read by `java.util.Properties`
+    pub fn to_properties_with_style(&self, style: &PropertiesStyle) -> String {
+        property_lines(self, &property_key(self), style)
+    }
+
+    /// Properties representation below the given key of the node like `servers[0]`
+    pub fn to_properties_at(&self, key: &str) -> String {
+        property_lines(self, key, &PropertiesStyle::default())
+    }
+
+    /// Values of the node and its children by their dotted path, array elements are always indexed
+    pub fn flatten(&self) -> LinkedHashMap<String, String> {
+        self.leaves()
+            .into_iter()
+            .map(|(key, leaf)| (key, leaf.value.to_string()))
+            .collect()
+    }
+
+    /// Scalars, empty objects and empty arrays of the node by their dotted path
+    pub(crate) fn leaves(&self) -> Vec<(String, &Node)> {
+        let mut leaves = vec![];
+        collect_leaves(self, &property_key(self), &mut leaves);
+        leaves
+    }
 }
 
 impl PartialEq for Node {
@@ -189,93 +501,291 @@ impl PartialOrd for Node {
 }
 
 // adapter
-impl Into<String> for &Node {
-    fn into(self) -> String {
-        match self.value {
-            // beginning just collects the backwards walk
-            NodeType::NONE => {
-                let mut data = String::new();
-                for children in &self.children {
-                    let next_part: String = children.into();
-                    data.push_str(&next_part);
-                }
-                return data.to_string();
+impl From<&Node> for String {
+    fn from(node: &Node) -> Self {
+        node.to_properties(false)
+    }
+}
+
+fn property_lines(node: &Node, key: &str, style: &PropertiesStyle) -> String {
+    let comment_line = |comment: &String| comment_line(comment, style);
+    let leading: String = node.comments.iter().map(comment_line).collect();
+    // properties have no comments at the end of a line, the trailing comment gets its own line
+    // above the key, below the key it would belong to the next key when read again
+    let trailing: String = node.trailing_comment.iter().map(comment_line).collect();
+    [leading, trailing, node_property_lines(node, key, style)].concat()
+}
+
+pub(crate) fn comment_line(comment: &str, style: &PropertiesStyle) -> String {
+    if comment.is_empty() {
+        return String::from("#\n");
+    }
+    let comment: String = comment
+        .chars()
+        .map(|character| escape_unicode(character, style))
+        .collect();
+    ["# ", &comment, "\n"].concat()
+}
+
+fn node_property_lines(node: &Node, key: &str, style: &PropertiesStyle) -> String {
+    match &node.value {
+        // beginning just collects the walk through the children
+        NodeType::NONE => {
+            let mut data = String::new();
+            for child in &node.children {
+                let child_key = child_key(key, &child.name);
+                data.push_str(&property_lines(child, &child_key, style));
             }
-            // walk backwards
-            _ => {
-                let mut data = String::new();
-                let new_data = parent_name(self);
-                // reverse order
-                let split_parts: Vec<&str> = new_data.split(".").collect();
-                for (index, node_part) in split_parts.iter().rev().enumerate() {
-                    data.push_str(node_part);
-                    if index != split_parts.len() - 1 {
-                        data.push_str(".");
-                    }
-                }
-                // push key and value onto string
-                data.push_str("=");
-                data.push_str(&self.value.to_string());
-                data.push_str("\n");
-                data
+            data
+        }
+        // arrays of objects, nested arrays or elements with a comma or comments can only be
+        // written with indexed keys, indexed keys are read as a single value so nested arrays
+        // are indexed too
+        NodeType::ARRAY(elements)
+            if !elements.is_empty()
+                && (style.indexed_arrays
+                    || is_element_key(key)
+                    || !elements.iter().all(|element| {
+                        element.value.is_scalar()
+                            && !element.value.to_string().contains(',')
+                            && element.comments.is_empty()
+                            && element.trailing_comment.is_none()
+                    })) =>
+        {
+            let mut data = String::new();
+            for (index, element) in elements.iter().enumerate() {
+                let element_key = [key, "[", &index.to_string(), "]"].concat();
+                data.push_str(&property_lines(element, &element_key, style));
+            }
+            data
+        }
+        // push key and value onto string
+        _ => property_line(key, &node.value.to_string(), style),
+    }
+}
+
+fn is_element_key(key: &str) -> bool {
+    matches!(
+        PropertyFileReader::key_segments(key).last(),
+        Some(KeySegment::Index(_))
+    )
+}
+
+fn property_line(key: &str, value: &str, style: &PropertiesStyle) -> String {
+    let key = escape_property_key(key, style);
+    let value = escape_property_value(value, style);
+    let delimiter = delimiter_text(style);
+    let value = match style.line_width {
+        Some(line_width) => {
+            let start = key.chars().count() + delimiter.chars().count();
+            wrap_property_value(&value, start, line_width)
+        }
+        None => value,
+    };
+    [&key, &delimiter, &value, "\n"].concat()
+}
+
+fn delimiter_text(style: &PropertiesStyle) -> String {
+    let delimiter = style.delimiter.value();
+    match (&style.delimiter, &style.delimiter_spacing) {
+        (Delimiter::Whitespace, _) | (_, DelimiterSpacing::None) => delimiter.to_string(),
+        (_, DelimiterSpacing::After) => format!("{} ", delimiter),
+        (_, DelimiterSpacing::Both) => format!(" {} ", delimiter),
+    }
+}
+
+/// Whitespace, delimiters and a leading comment character would end or hide the key
+fn escape_property_key(key: &str, style: &PropertiesStyle) -> String {
+    let mut escaped = String::new();
+    for (index, character) in key.chars().enumerate() {
+        match character {
+            ' ' | '=' | ':' => escaped.extend(['\\', character]),
+            '#' | '!' if index == 0 => escaped.extend(['\\', character]),
+            _ => escaped.push_str(&escape_character(character, style)),
+        }
+    }
+    escaped
+}
+
+/// Leading whitespace of a value would be skipped by the reader, after a whitespace delimiter
+/// also a leading `=` or `:`
+fn escape_property_value(value: &str, style: &PropertiesStyle) -> String {
+    let mut escaped = String::new();
+    for (index, character) in value.chars().enumerate() {
+        match character {
+            ' ' if index == 0 => escaped.push_str("\\ "),
+            '=' | ':' if index == 0 && matches!(style.delimiter, Delimiter::Whitespace) => {
+                escaped.extend(['\\', character])
             }
+            _ => escaped.push_str(&escape_character(character, style)),
         }
     }
+    escaped
+}
+
+fn escape_character(character: char, style: &PropertiesStyle) -> String {
+    match character {
+        '\\' => String::from("\\\\"),
+        '\t' => String::from("\\t"),
+        '\n' => String::from("\\n"),
+        '\r' => String::from("\\r"),
+        '\x0c' => String::from("\\f"),
+        // other control characters would not survive a text editor
+        _ if character.is_control() => unicode_escape(character),
+        _ => escape_unicode(character, style),
+    }
+}
+
+fn escape_unicode(character: char, style: &PropertiesStyle) -> String {
+    if style.escape_unicode && !character.is_ascii() {
+        return unicode_escape(character);
+    }
+    character.to_string()
+}
+
+/// `\uXXXX` escape, characters beyond the basic multilingual plane become a surrogate pair
+fn unicode_escape(character: char) -> String {
+    let mut utf16 = [0; 2];
+    character
+        .encode_utf16(&mut utf16)
+        .iter()
+        .map(|unit| format!("\\u{:04x}", unit))
+        .collect()
+}
+
+/// Breaks an escaped value after spaces, continuation lines are indented which the reader skips
+fn wrap_property_value(value: &str, start: usize, line_width: usize) -> String {
+    let mut wrapped = String::new();
+    let mut length = start;
+    let mut line_empty = true;
+    for word in value.split_inclusive(' ') {
+        let word_length = word.chars().count();
+        // a continuation line must not start with whitespace, it would be skipped
+        if !line_empty && length + word_length > line_width && !word.starts_with(' ') {
+            wrapped.push_str("\\\n");
+            wrapped.push_str(CONTINUATION_INDENT);
+            length = CONTINUATION_INDENT.len();
+        }
+        wrapped.push_str(word);
+        length += word_length;
+        line_empty = false;
+    }
+    wrapped
+}
+
+const CONTINUATION_INDENT: &str = "    ";
+
+fn collect_leaves<'a>(node: &'a Node, key: &str, leaves: &mut Vec<(String, &'a Node)>) {
+    match &node.value {
+        NodeType::NONE if !node.children.is_empty() => {
+            for child in &node.children {
+                collect_leaves(child, &child_key(key, &child.name), leaves);
+            }
+        }
+        NodeType::ARRAY(elements) if !elements.is_empty() => {
+            for (index, element) in elements.iter().enumerate() {
+                let element_key = [key, "[", &index.to_string(), "]"].concat();
+                collect_leaves(element, &element_key, leaves);
+            }
+        }
+        _ => leaves.push((key.to_string(), node)),
+    }
+}
+
+fn child_key(key: &str, name: &str) -> String {
+    if key.is_empty() {
+        name.to_string()
+    } else {
+        [key, ".", name].concat()
+    }
+}
+
+// walk backwards
+fn property_key(node: &Node) -> String {
+    let new_data = parent_name(node);
+    // reverse order
+    let split_parts: Vec<&str> = new_data.split('.').collect();
+    let mut data = String::new();
+    for (index, node_part) in split_parts.iter().rev().enumerate() {
+        data.push_str(node_part);
+        if index != split_parts.len() - 1 {
+            data.push('.');
+        }
+    }
+    data
 }
 
 fn parent_name(node: &Node) -> String {
     let mut data: String = node.name.to_string();
-    if node.parent.is_some() {
-        let new_data = parent_name(&node.parent.as_ref().unwrap());
-        data.push_str(".");
+    if let Some(parent) = &node.parent {
+        let new_data = parent_name(parent);
+        data.push('.');
         data.push_str(&new_data.to_owned());
     }
     data
 }
 
-impl Into<JsonValue> for &Node {
-    fn into(self) -> JsonValue {
-        let data = match &self.value {
+impl From<&Node> for JsonValue {
+    fn from(node: &Node) -> Self {
+        match &node.value {
             NodeType::NONE => {
                 let mut data = JsonValue::new_object();
-                for children in &self.children {
+                for children in &node.children {
                     data[children.name.clone()] = children.into();
                 }
-                return data;
-            }
-            NodeType::BOOLEAN(value) => JsonValue::Boolean(value.clone()),
-            NodeType::NUMERIC(value) => {
-                // let num_value = value.parse::<i32>().unwrap();
-                // JsonValue::Number(num_value.into())
-                return match value.parse::<f64>() {
-                    Ok(parsed_value) => JsonValue::Number(parsed_value.into()),
-                    Err(_) => match value.parse::<usize>() {
-                        Ok(parsed_value) => JsonValue::Number(parsed_value.into()),
-                        Err(_) => JsonValue::Number(0.into()),
-                    },
-                };
+                data
             }
+            NodeType::NULL => JsonValue::Null,
+            NodeType::BOOLEAN(value) => JsonValue::Boolean(*value),
+            // the number keeps its lexeme like 1.10 instead of going through a double
+            NodeType::NUMERIC(value) => match json::parse(value) {
+                Ok(JsonValue::Number(number)) if is_same_json_number(value, &number) => {
+                    JsonValue::Number(number)
+                }
+                // json numbers are limited to 64 bit mantissas, keep the value as string instead
+                _ => JsonValue::String(value.clone()),
+            },
             NodeType::STRING(value) => JsonValue::String(value.clone()),
             NodeType::OBJECT(value) => JsonValue::String(value.clone()),
             NodeType::ARRAY(value) => {
                 let mut array = vec![];
                 for element in value {
-                    let json_value: JsonValue = JsonValue::String(element.clone());
+                    let json_value: JsonValue = element.into();
                     array.push(json_value);
                 }
                 JsonValue::Array(array)
             }
-        };
-        data
+        }
     }
 }
 
-impl Into<Yaml> for &Node {
-    fn into(self) -> Yaml {
-        let data = match &self.value {
+fn is_same_json_number(lexeme: &str, number: &json::number::Number) -> bool {
+    let (positive, mantissa, exponent) = number.as_parts();
+    let sign = if positive { "" } else { "-" };
+    is_same_number(lexeme, &format!("{}{}e{}", sign, mantissa, exponent))
+}
+
+/// The toml floats nan and infinity, which have no representation in json
+pub(crate) fn is_nan_or_infinity(lexeme: &str) -> bool {
+    matches!(lexeme.trim_start_matches(['+', '-']), "nan" | "inf")
+}
+
+/// Yaml spelling of a float lexeme, nan and infinity are written like `.nan` and `-.inf`
+fn yaml_float(lexeme: &str) -> String {
+    match lexeme {
+        _ if !is_nan_or_infinity(lexeme) => lexeme.to_string(),
+        _ if lexeme.ends_with("nan") => String::from(".nan"),
+        "-inf" => String::from("-.inf"),
+        _ => String::from(".inf"),
+    }
+}
+
+impl From<&Node> for Yaml {
+    fn from(node: &Node) -> Self {
+        match &node.value {
             NodeType::NONE => {
                 let mut map: LinkedHashMap<Yaml, Yaml> = LinkedHashMap::new();
-                for child in &self.children {
+                for child in &node.children {
                     let key = Yaml::from_str(&child.name);
                     let yaml_node: Yaml = child.into();
                     if !map.contains_key(&key) {
@@ -284,21 +794,28 @@ impl Into<Yaml> for &Node {
                     }
                     map.insert(key, yaml_node);
                 }
-                return Yaml::Hash(map);
+                Yaml::Hash(map)
             }
+            NodeType::NULL => Yaml::Null,
             NodeType::BOOLEAN(value) => Yaml::from_str(&value.to_string()),
-            NodeType::NUMERIC(value) => Yaml::from_str(&value),
-            NodeType::STRING(value) => Yaml::from_str(&value),
-            NodeType::OBJECT(value) => Yaml::from_str(&value),
+            // reals are emitted with their lexeme, integers only if they are not changed by i64
+            NodeType::NUMERIC(value) => match value.parse::<i64>() {
+                Ok(parsed_value) if parsed_value.to_string() == *value => {
+                    Yaml::Integer(parsed_value)
+                }
+                _ => Yaml::Real(yaml_float(value)),
+            },
+            // strings must not be guessed again, the emitter quotes ambiguous ones like "true"
+            NodeType::STRING(value) => Yaml::String(value.clone()),
+            NodeType::OBJECT(value) => Yaml::from_str(value),
             NodeType::ARRAY(value) => {
                 let mut array = vec![];
                 for element in value {
-                    let yaml_value: Yaml = Yaml::from_str(&element);
+                    let yaml_value: Yaml = element.into();
                     array.push(yaml_value);
                 }
                 Yaml::Array(array)
             }
-        };
-        data
+        }
     }
 }
@@ -1,8 +1,10 @@
 #[cfg(test)]
 mod tests {
     use crate::node::NodeType::{ARRAY, NUMERIC, STRING};
-    use crate::node::{Node, NodeType};
+    use crate::node::{Node, NodeType, PropertiesStyle};
+    use crate::nodes::ArrayMerge;
     use crate::property_file_reader::PropertyFileReader;
+    use yaml_rust::Yaml;
 
     #[test]
     fn node_value_retrieve_type_string() {
@@ -30,7 +32,7 @@ mod tests {
         let node_type = NodeType::parse(value);
         match node_type {
             NodeType::BOOLEAN(v) => {
-                assert_eq!(v, false);
+                assert!(!v);
             }
             _ => panic!("Wrong type"),
         }
@@ -42,7 +44,7 @@ mod tests {
         let node_type = NodeType::parse(value);
         match node_type {
             NodeType::BOOLEAN(v) => {
-                assert_eq!(v, true);
+                assert!(v);
             }
             _ => panic!("Wrong type"),
         }
@@ -54,7 +56,7 @@ mod tests {
         let node_type = NodeType::parse(value);
         match node_type {
             NodeType::BOOLEAN(v) => {
-                assert_eq!(v, false);
+                assert!(!v);
             }
             _ => panic!("Wrong type"),
         }
@@ -66,7 +68,7 @@ mod tests {
         let node_type = NodeType::parse(value);
         match node_type {
             NodeType::BOOLEAN(v) => {
-                assert_eq!(v, true);
+                assert!(v);
             }
             _ => panic!("Wrong type"),
         }
@@ -115,7 +117,8 @@ mod tests {
         let node_type = NodeType::parse(value);
         match node_type {
             ARRAY(v) => {
-                assert_eq!(v, expected_value);
+                let values: Vec<String> = v.iter().map(|node| node.value.to_string()).collect();
+                assert_eq!(values, expected_value);
             }
             _ => panic!("Wrong type"),
         }
@@ -128,7 +131,8 @@ mod tests {
         let node_type = NodeType::parse(value);
         match node_type {
             ARRAY(v) => {
-                assert_eq!(v, expected_value);
+                let values: Vec<String> = v.iter().map(|node| node.value.to_string()).collect();
+                assert_eq!(values, expected_value);
             }
             _ => panic!("Wrong type"),
         }
@@ -141,7 +145,8 @@ mod tests {
         let node_type = NodeType::parse(value);
         match node_type {
             ARRAY(v) => {
-                assert_eq!(v, expected_value);
+                let values: Vec<String> = v.iter().map(|node| node.value.to_string()).collect();
+                assert_eq!(values, expected_value);
             }
             _ => panic!("Wrong type"),
         }
@@ -300,7 +305,10 @@ mod tests {
 
     #[test]
     fn node_type_to_string_array() {
-        let node_type = ARRAY(vec![String::from("test"), String::from("test2")]);
+        let node_type = ARRAY(vec![
+            Node::new_array_element(0, STRING(String::from("test"))),
+            Node::new_array_element(1, STRING(String::from("test2"))),
+        ]);
         let expected_value = String::from("test,test2");
         assert_eq!(expected_value, node_type.to_string())
     }
@@ -308,7 +316,7 @@ mod tests {
     #[test]
     fn new_json_node() {
         let key = "name";
-        let node = Node::new_from_name(&key);
+        let node = Node::new_from_name(key);
 
         assert_eq!(key, node.name);
         assert_eq!(0, node.level);
@@ -337,4 +345,272 @@ mod tests {
         let property_representation: String = test_node.into();
         assert_eq!("test.test2=value\n", property_representation);
     }
+
+    #[test]
+    fn into_toml() {
+        let mut node = Node::new_from_name("test");
+        PropertyFileReader::create_child_nodes(&mut node, &mut vec!["test2"], "8080");
+
+        let test_node = &node.to_owned();
+        let toml_representation: toml::Value = test_node.into();
+        assert_eq!(
+            Some(&toml::Value::Integer(8080)),
+            toml_representation.get("test2")
+        );
+    }
+
+    #[test]
+    fn into_property_string_array_of_objects() {
+        let mut node = Node::new_from_name("servers");
+        let mut first = Node::new_array_element(0, NodeType::NONE);
+        PropertyFileReader::create_child_nodes(&mut first, &mut vec!["host"], "alpha");
+        let mut second = Node::new_array_element(1, NodeType::NONE);
+        PropertyFileReader::create_child_nodes(&mut second, &mut vec!["host"], "beta");
+        node.value = ARRAY(vec![first, second]);
+
+        let property_representation: String = (&node).into();
+        assert_eq!(
+            "servers[0].host=alpha\nservers[1].host=beta\n",
+            property_representation
+        );
+    }
+
+    #[test]
+    fn into_json_array_of_objects() {
+        let mut node = Node::new_from_name("servers");
+        let mut first = Node::new_array_element(0, NodeType::NONE);
+        PropertyFileReader::create_child_nodes(&mut first, &mut vec!["host"], "alpha");
+        let nested = Node::new_array_element(1, NodeType::parse("a,b"));
+        node.value = ARRAY(vec![first, nested]);
+
+        let data = json::stringify(&node);
+        assert_eq!("[{\"host\":\"alpha\"},[\"a\",\"b\"]]", data);
+    }
+
+    #[test]
+    fn to_properties_indexed_arrays() {
+        let mut node = Node::new_from_name("test");
+        PropertyFileReader::create_child_nodes(&mut node, &mut vec!["list"], "a,b");
+
+        assert_eq!("test.list=a,b\n", node.to_properties(false));
+        assert_eq!("test.list[0]=a\ntest.list[1]=b\n", node.to_properties(true));
+    }
+
+    #[test]
+    fn to_properties_element_with_comma() {
+        let mut node = Node::new_from_name("list");
+        node.value = ARRAY(vec![
+            Node::new_array_element(0, STRING(String::from("a,b"))),
+            Node::new_array_element(1, STRING(String::from("c"))),
+        ]);
+
+        assert_eq!("list[0]=a,b\nlist[1]=c\n", node.to_properties(false));
+    }
+
+    #[test]
+    fn to_properties_element_with_comments() {
+        let mut node = array_node(&["a", "b"]);
+        if let ARRAY(elements) = &mut node.value {
+            elements[1].comments = vec!["backup".to_string()];
+        }
+
+        assert_eq!(
+            "hosts[0]=a\n# backup\nhosts[1]=b\n",
+            node.to_properties(false)
+        );
+    }
+
+    #[test]
+    fn into_yaml_string_is_not_guessed() {
+        let mut node = Node::new_from_name("flag");
+        node.value = STRING(String::from("true"));
+
+        let yaml: Yaml = (&node).into();
+        assert_eq!(Yaml::String(String::from("true")), yaml);
+    }
+
+    #[test]
+    fn node_value_retrieve_type_leading_zero_is_string() {
+        assert_eq!(STRING(String::from("007")), NodeType::parse("007"));
+        assert_eq!(STRING(String::from("1e5")), NodeType::parse("1e5"));
+        assert_eq!(STRING(String::from("NaN")), NodeType::parse("NaN"));
+        assert_eq!(NUMERIC(String::from("-0.5")), NodeType::parse("-0.5"));
+    }
+
+    #[test]
+    fn is_canonical_number() {
+        assert!(NUMERIC(String::from("8080")).is_canonical_number());
+        assert!(NUMERIC(String::from("0.5")).is_canonical_number());
+        assert!(!NUMERIC(String::from("1.10")).is_canonical_number());
+        assert!(!NUMERIC(String::from("9007199254740993")).is_canonical_number());
+    }
+
+    #[test]
+    fn into_json_keeps_number_lexeme() {
+        let mut node = Node::new_from_name("version");
+        node.value = NUMERIC(String::from("1.10"));
+        assert_eq!("1.10", json::stringify(&node));
+
+        node.value = NUMERIC(String::from("18446744073709551615"));
+        assert_eq!("18446744073709551615", json::stringify(&node));
+
+        // does not fit into the json number representation
+        node.value = NUMERIC(String::from("123456789012345678901234"));
+        assert_eq!("\"123456789012345678901234\"", json::stringify(&node));
+    }
+
+    #[test]
+    fn treat_as_strings() {
+        let mut node = Node::new_from_name("server");
+        PropertyFileReader::create_child_nodes(&mut node, &mut vec!["port"], "8080");
+        PropertyFileReader::create_child_nodes(&mut node, &mut vec!["version"], "1.10");
+
+        node.treat_as_strings("server", &[String::from("server.port")], false);
+        assert_eq!(STRING(String::from("8080")), node.children[0].value);
+        assert_eq!(NUMERIC(String::from("1.10")), node.children[1].value);
+
+        node.treat_as_strings("server", &[], true);
+        assert_eq!(STRING(String::from("1.10")), node.children[1].value);
+    }
+
+    #[test]
+    fn into_null() {
+        let mut node = Node::new_from_name("test");
+        node.value = NodeType::NULL;
+
+        assert_eq!("null", json::stringify(&node));
+        let yaml: Yaml = (&node).into();
+        assert_eq!(Yaml::Null, yaml);
+        assert_eq!("test=\n", node.to_properties(false));
+    }
+
+    fn array_node(values: &[&str]) -> Node {
+        let mut node = Node::new_from_name("hosts");
+        PropertyFileReader::create_child_nodes(&mut node, &mut vec![], &values.join(","));
+        node
+    }
+
+    #[test]
+    fn override_with_nested_values() {
+        let mut node = Node::new_from_name("server");
+        PropertyFileReader::create_child_nodes(&mut node, &mut vec!["port"], "8080");
+        PropertyFileReader::create_child_nodes(&mut node, &mut vec!["host"], "localhost");
+        let mut other = Node::new_from_name("server");
+        PropertyFileReader::create_child_nodes(&mut other, &mut vec!["port"], "9090");
+        PropertyFileReader::create_child_nodes(&mut other, &mut vec!["ssl", "enabled"], "true");
+
+        node.override_with(&other, &ArrayMerge::Replace);
+        assert_eq!(3, node.children.len());
+        assert_eq!(NUMERIC(String::from("9090")), node.children[0].value);
+        assert_eq!(STRING(String::from("localhost")), node.children[1].value);
+        assert_eq!("ssl", node.children[2].name);
+        assert_eq!(NodeType::BOOLEAN(true), node.children[2].children[0].value);
+    }
+
+    #[test]
+    fn override_with_scalar_replaces_object() {
+        let mut node = Node::new_from_name("server");
+        PropertyFileReader::create_child_nodes(&mut node, &mut vec!["port"], "8080");
+        let mut other = Node::new_from_name("server");
+        other.value = STRING(String::from("disabled"));
+
+        node.override_with(&other, &ArrayMerge::Replace);
+        assert!(node.children.is_empty());
+        assert_eq!(STRING(String::from("disabled")), node.value);
+    }
+
+    #[test]
+    fn override_with_arrays() {
+        let other = array_node(&["c", "d"]);
+
+        let mut node = array_node(&["a", "b"]);
+        node.override_with(&other, &ArrayMerge::Replace);
+        assert_eq!("c,d", node.value.to_string());
+
+        let mut node = array_node(&["a", "b"]);
+        node.override_with(&other, &ArrayMerge::Append);
+        assert_eq!("a,b,c,d", node.value.to_string());
+        match &node.value {
+            ARRAY(elements) => assert_eq!("2", elements[2].name),
+            _ => panic!("Wrong type"),
+        }
+
+        let mut node = array_node(&["a", "b"]);
+        node.override_with(&other, &ArrayMerge::Index);
+        assert_eq!("c,d", node.value.to_string());
+
+        let mut node = array_node(&["a", "b", "e"]);
+        node.override_with(&other, &ArrayMerge::Index);
+        assert_eq!("c,d,e", node.value.to_string());
+    }
+
+    #[test]
+    fn to_properties_comments() {
+        let mut node = PropertyFileReader::property_to_node("db.url", "localhost").unwrap();
+        node.comments = vec!["database".to_string()];
+        node.children[0].comments = vec!["".to_string(), "reporting".to_string()];
+        node.children[0].trailing_comment = Some("local only".to_string());
+
+        assert_eq!(
+            "# database\n#\n# reporting\n# local only\ndb.url=localhost\n",
+            node.to_properties(false)
+        );
+    }
+
+    fn string_node(key: &str, value: &str) -> Node {
+        let mut node = PropertyFileReader::property_to_node(key, "").unwrap();
+        let mut leaf = &mut node;
+        while !leaf.children.is_empty() {
+            leaf = &mut leaf.children[0];
+        }
+        leaf.value = STRING(value.to_string());
+        node
+    }
+
+    #[test]
+    fn to_properties_escaped() {
+        let node = string_node("key with=and:", "  two spaces\nnew line\\path");
+        assert_eq!(
+            "key\\ with\\=and\\:=\\  two spaces\\nnew line\\\\path\n",
+            node.to_properties(false)
+        );
+
+        let node = string_node("#hash", "#value");
+        assert_eq!("\\#hash=#value\n", node.to_properties(false));
+    }
+
+    #[test]
+    fn to_properties_escape_unicode() {
+        let node = string_node("greeting", "Grüße 😀");
+        assert_eq!("greeting=Grüße 😀\n", node.to_properties(false));
+
+        let style = PropertiesStyle {
+            escape_unicode: true,
+            ..PropertiesStyle::default()
+        };
+        assert_eq!(
+            "greeting=Gr\\u00fc\\u00dfe \\ud83d\\ude00\n",
+            node.to_properties_with_style(&style)
+        );
+    }
+
+    #[test]
+    fn to_properties_line_width() {
+        let node = string_node("text", "the quick brown fox jumps over the lazy dog");
+        let style = PropertiesStyle {
+            line_width: Some(20),
+            ..PropertiesStyle::default()
+        };
+        assert_eq!(
+            "text=the quick \\\n    brown fox jumps \\\n    over the lazy \\\n    dog\n",
+            node.to_properties_with_style(&style)
+        );
+
+        // words longer than the line are not broken
+        let node = string_node("url", "https://example.com/a/very/long/path");
+        assert_eq!(
+            "url=https://example.com/a/very/long/path\n",
+            node.to_properties_with_style(&style)
+        );
+    }
 }
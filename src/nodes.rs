@@ -1,23 +1,183 @@
-use std::slice::Iter;
+use std::fmt::Display;
+use std::slice::{Iter, IterMut};
+use std::str::FromStr;
 
-use crate::node::Node;
+use crate::errors::ProconError;
+use crate::node::{Node, NodeType};
+use crate::property_file_reader::{KeySegment, PropertyFileReader};
+use linked_hash_map::LinkedHashMap;
 use log::debug;
 
-#[derive(Debug)]
+/// Strategy to merge arrays of multiple input files
+#[derive(Debug, Clone)]
+pub enum ArrayMerge {
+    Replace,
+    Append,
+    Index,
+}
+
+impl Display for ArrayMerge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
+impl FromStr for ArrayMerge {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<ArrayMerge, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "replace" => Ok(ArrayMerge::Replace),
+            "append" => Ok(ArrayMerge::Append),
+            "index" => Ok(ArrayMerge::Index),
+            &_ => Err(format!("Unknown array merge strategy {}", input)),
+        }
+    }
+}
+
+/// Treatment of yaml tagged values like `!Ref bucket` by writers of other formats
+#[derive(Debug, Clone)]
+pub enum TagPolicy {
+    Keep,
+    Stringify,
+    Error,
+}
+
+impl Display for TagPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
+impl FromStr for TagPolicy {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<TagPolicy, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "keep" => Ok(TagPolicy::Keep),
+            "stringify" => Ok(TagPolicy::Stringify),
+            "error" => Ok(TagPolicy::Error),
+            &_ => Err(format!("Unknown tagged values policy {}", input)),
+        }
+    }
+}
+
+/// Spaces around the delimiter of written properties
+#[derive(Debug, Clone, Default)]
+pub enum DelimiterSpacing {
+    #[default]
+    None,
+    After,
+    Both,
+}
+
+impl Display for DelimiterSpacing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
+impl FromStr for DelimiterSpacing {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<DelimiterSpacing, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "none" => Ok(DelimiterSpacing::None),
+            "after" => Ok(DelimiterSpacing::After),
+            "both" => Ok(DelimiterSpacing::Both),
+            &_ => Err(format!("Unknown delimiter spacing {}", input)),
+        }
+    }
+}
+
+/// Quoting of written yaml strings
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum QuoteStyle {
+    /// only strings which would be read as another type or contain yaml syntax
+    #[default]
+    Ambiguous,
+    Always,
+}
+
+impl Display for QuoteStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
+impl FromStr for QuoteStyle {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<QuoteStyle, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "ambiguous" => Ok(QuoteStyle::Ambiguous),
+            "always" => Ok(QuoteStyle::Always),
+            &_ => Err(format!("Unknown quote style {}", input)),
+        }
+    }
+}
+
+/// Style of written yaml strings with line breaks
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum MultilineStyle {
+    /// a double quoted string with `\n` escapes
+    #[default]
+    Quoted,
+    /// a `|` block keeping every line break
+    Literal,
+    /// a `>` block with the line breaks as blank lines
+    Folded,
+}
+
+impl Display for MultilineStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
+impl FromStr for MultilineStyle {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<MultilineStyle, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "quoted" => Ok(MultilineStyle::Quoted),
+            "literal" => Ok(MultilineStyle::Literal),
+            "folded" => Ok(MultilineStyle::Folded),
+            &_ => Err(format!("Unknown multiline style {}", input)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct Nodes {
     nodes: Vec<Node>,
+    /// comment lines below the last key of the file
+    pub trailing_comments: Vec<String>,
 }
 
 #[allow(dead_code)]
+impl Default for Nodes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Nodes {
     pub fn new() -> Nodes {
-        Nodes { nodes: Vec::new() }
+        Nodes {
+            nodes: Vec::new(),
+            trailing_comments: Vec::new(),
+        }
     }
 
     pub fn iter(&self) -> Iter<'_, Node> {
         self.nodes.iter()
     }
 
+    pub fn iter_mut(&mut self) -> IterMut<'_, Node> {
+        self.nodes.iter_mut()
+    }
+
     pub fn push(&mut self, node: Node) {
         debug!("Add node to the yaml nodes {:?} ", node);
         self.nodes.push(node);
@@ -44,11 +204,128 @@ impl Nodes {
             return;
         }
         for existing_node in &mut self.nodes.iter_mut() {
-            let to_add = existing_node.find_common_node(&new_node);
+            let to_add = existing_node.find_common_node(new_node);
             if !to_add {
                 return;
             }
         }
         self.nodes.push(new_node.to_owned());
     }
+
+    /// Turns values into strings which should not be read as numbers or booleans
+    pub fn treat_as_strings(&mut self, string_keys: &[String], strict_numbers: bool) {
+        for node in &mut self.nodes {
+            let key = node.name.clone();
+            node.treat_as_strings(&key, string_keys, strict_numbers);
+        }
+    }
+
+    /// Deep merges other nodes into these nodes, values of the other nodes win
+    pub fn override_with(&mut self, other: &Nodes, array_merge: &ArrayMerge) {
+        for other_node in other.iter() {
+            match self
+                .nodes
+                .iter_mut()
+                .find(|node| node.name == other_node.name)
+            {
+                Some(existing_node) => existing_node.override_with(other_node, array_merge),
+                None => self.nodes.push(other_node.to_owned()),
+            }
+        }
+    }
+
+    /// Values of all nodes by their dotted path
+    pub fn flatten(&self) -> LinkedHashMap<String, String> {
+        let mut values = LinkedHashMap::new();
+        for node in self.iter() {
+            values.extend(node.flatten());
+        }
+        values
+    }
+
+    /// Top-level nodes named by the dotted keys of all values like `servers[0].host`
+    pub fn flat(&self) -> Nodes {
+        let mut flat_nodes = Nodes::new();
+        for node in self.iter() {
+            for (key, leaf) in node.leaves() {
+                let mut flat_node = leaf.clone();
+                flat_node.name = key;
+                flat_node.level = 0;
+                flat_node.parent = None;
+                flat_nodes.push(flat_node);
+            }
+        }
+        flat_nodes
+    }
+
+    /// Node of a dotted key with array indices like `servers[0].host`
+    pub fn find(&self, key: &str) -> Option<&Node> {
+        self.find_segments(&PropertyFileReader::key_segments(key))
+    }
+
+    fn find_segments(&self, key_segments: &[KeySegment]) -> Option<&Node> {
+        // keys starting with an index address a root list
+        let (name, rest) = match key_segments.first()? {
+            KeySegment::Name(name) => (*name, &key_segments[1..]),
+            KeySegment::Index(_) => ("", key_segments),
+        };
+        self.iter().find(|node| node.name == name)?.find(rest)
+    }
+
+    /// Mutable node of a dotted key with array indices like `servers[0].host`
+    pub fn find_mut(&mut self, key: &str) -> Option<&mut Node> {
+        let key_segments = PropertyFileReader::key_segments(key);
+        let (name, rest) = match key_segments.first()? {
+            KeySegment::Name(name) => (*name, &key_segments[1..]),
+            KeySegment::Index(_) => ("", &key_segments[..]),
+        };
+        self.iter_mut()
+            .find(|node| node.name == name)?
+            .find_mut(rest)
+    }
+
+    /// Sets the value of a dotted key, missing nodes are created
+    ///
+    /// The value is a single scalar, commas are kept. Array indices can address an existing
+    /// element or append one at the end of the array
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), ProconError> {
+        self.check_indices(key)?;
+        if let Some(node) = PropertyFileReader::property_to_node(key, value) {
+            let mut patch = Nodes::new();
+            patch.push(node);
+            if let Some(leaf) = patch.find_mut(key) {
+                leaf.value = NodeType::parse_scalar(value);
+            }
+            self.override_with(&patch, &ArrayMerge::Index);
+        }
+        Ok(())
+    }
+
+    /// Indices beyond the end of an array would leave a gap
+    fn check_indices(&self, key: &str) -> Result<(), ProconError> {
+        let key_segments = PropertyFileReader::key_segments(key);
+        for (position, segment) in key_segments.iter().enumerate() {
+            let KeySegment::Index(index) = segment else {
+                continue;
+            };
+            let array = match position {
+                // root list
+                0 => self.iter().find(|node| node.name.is_empty()),
+                _ => self.find_segments(&key_segments[..position]),
+            };
+            let length = match array.map(|node| &node.value) {
+                Some(NodeType::ARRAY(elements)) => elements.len(),
+                _ => 0,
+            };
+            if *index > length {
+                return Err(ProconError {
+                    message: format!(
+                        "Index {} of {} is out of range, the array has {} elements",
+                        index, key, length
+                    ),
+                });
+            }
+        }
+        Ok(())
+    }
 }
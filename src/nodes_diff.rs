@@ -0,0 +1,105 @@
+use json::JsonValue;
+use linked_hash_map::LinkedHashMap;
+
+use crate::nodes::Nodes;
+
+#[cfg(test)]
+#[path = "./nodes_diff_test.rs"]
+mod nodes_diff_test;
+
+/// Change of a single flattened key between two files
+#[derive(Debug, PartialEq)]
+pub enum Difference {
+    Added {
+        key: String,
+        value: String,
+    },
+    Removed {
+        key: String,
+        value: String,
+    },
+    Changed {
+        key: String,
+        value: String,
+        other_value: String,
+    },
+}
+
+/// Compares the flattened keys of both nodes, the order follows the first and then the other nodes
+pub fn diff(nodes: &Nodes, other_nodes: &Nodes) -> Vec<Difference> {
+    let values: LinkedHashMap<String, String> = nodes.flatten();
+    let other_values: LinkedHashMap<String, String> = other_nodes.flatten();
+
+    let mut differences: Vec<Difference> = vec![];
+    for (key, value) in values.iter() {
+        match other_values.get(key) {
+            None => differences.push(Difference::Removed {
+                key: key.clone(),
+                value: value.clone(),
+            }),
+            Some(other_value) if other_value != value => differences.push(Difference::Changed {
+                key: key.clone(),
+                value: value.clone(),
+                other_value: other_value.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+    for (key, other_value) in other_values.iter() {
+        if !values.contains_key(key) {
+            differences.push(Difference::Added {
+                key: key.clone(),
+                value: other_value.clone(),
+            });
+        }
+    }
+    differences
+}
+
+/// One line per difference, prefixed with + for added, - for removed and ~ for changed keys
+pub fn to_human(differences: &[Difference]) -> String {
+    let mut content = String::new();
+    for difference in differences {
+        let line = match difference {
+            Difference::Added { key, value } => ["+ ", key, ": ", value].concat(),
+            Difference::Removed { key, value } => ["- ", key, ": ", value].concat(),
+            Difference::Changed {
+                key,
+                value,
+                other_value,
+            } => ["~ ", key, ": ", value, " -> ", other_value].concat(),
+        };
+        content.push_str(&line);
+        content.push('\n');
+    }
+    content
+}
+
+/// Json object with the added, removed and changed keys
+pub fn to_json(differences: &[Difference]) -> String {
+    let mut added = JsonValue::new_object();
+    let mut removed = JsonValue::new_object();
+    let mut changed = JsonValue::new_object();
+    for difference in differences {
+        match difference {
+            Difference::Added { key, value } => added[key.as_str()] = value.as_str().into(),
+            Difference::Removed { key, value } => removed[key.as_str()] = value.as_str().into(),
+            Difference::Changed {
+                key,
+                value,
+                other_value,
+            } => {
+                let mut change = JsonValue::new_object();
+                change["old"] = value.as_str().into();
+                change["new"] = other_value.as_str().into();
+                changed[key.as_str()] = change;
+            }
+        }
+    }
+
+    let mut json_data = JsonValue::new_object();
+    json_data["added"] = added;
+    json_data["removed"] = removed;
+    json_data["changed"] = changed;
+    json_data.pretty(1)
+}
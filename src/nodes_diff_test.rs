@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod tests {
+    use crate::node::Node;
+    use crate::nodes::Nodes;
+    use crate::nodes_diff::{diff, to_human, to_json, Difference};
+    use crate::property_file_reader::PropertyFileReader;
+
+    fn server_nodes(properties: &[(&str, &str)]) -> Nodes {
+        let mut nodes = Nodes::new();
+        let mut node = Node::new_from_name("server");
+        for (key, value) in properties {
+            PropertyFileReader::create_child_nodes(&mut node, &mut key.split('.').collect(), value);
+        }
+        nodes.push(node);
+        nodes
+    }
+
+    #[test]
+    fn diff_equal_nodes() {
+        let nodes = server_nodes(&[("port", "8080"), ("hosts", "a,b")]);
+        let other_nodes = server_nodes(&[("port", "8080"), ("hosts", "a,b")]);
+
+        assert!(diff(&nodes, &other_nodes).is_empty());
+    }
+
+    #[test]
+    fn diff_added_removed_changed() {
+        let nodes = server_nodes(&[("port", "8080"), ("host", "localhost"), ("hosts", "a,b")]);
+        let other_nodes = server_nodes(&[("port", "9090"), ("hosts", "a,c"), ("ssl", "true")]);
+
+        let differences = diff(&nodes, &other_nodes);
+        assert_eq!(
+            vec![
+                Difference::Changed {
+                    key: String::from("server.port"),
+                    value: String::from("8080"),
+                    other_value: String::from("9090"),
+                },
+                Difference::Removed {
+                    key: String::from("server.host"),
+                    value: String::from("localhost"),
+                },
+                Difference::Changed {
+                    key: String::from("server.hosts[1]"),
+                    value: String::from("b"),
+                    other_value: String::from("c"),
+                },
+                Difference::Added {
+                    key: String::from("server.ssl"),
+                    value: String::from("true"),
+                },
+            ],
+            differences
+        );
+        assert_eq!(
+            "~ server.port: 8080 -> 9090\n- server.host: localhost\n~ server.hosts[1]: b -> c\n+ server.ssl: true\n",
+            to_human(&differences)
+        );
+    }
+
+    #[test]
+    fn diff_to_json() {
+        let nodes = server_nodes(&[("port", "8080")]);
+        let other_nodes = server_nodes(&[("port", "9090")]);
+
+        let json_data = json::parse(&to_json(&diff(&nodes, &other_nodes))).unwrap();
+        assert!(json_data["added"].is_empty());
+        assert!(json_data["removed"].is_empty());
+        assert_eq!("8080", json_data["changed"]["server.port"]["old"]);
+        assert_eq!("9090", json_data["changed"]["server.port"]["new"]);
+    }
+}
@@ -0,0 +1,102 @@
+use jsonschema::paths::PathChunk;
+use jsonschema::JSONSchema;
+use linked_hash_map::LinkedHashMap;
+
+use crate::errors::ProconError;
+use crate::nodes::Nodes;
+use crate::nodes_writer::to_json_value;
+
+#[cfg(test)]
+#[path = "./nodes_validator_test.rs"]
+mod nodes_validator_test;
+
+/// Schema violation of a single key
+#[derive(Debug, PartialEq)]
+pub struct Violation {
+    pub key: String,
+    pub message: String,
+    pub line_number: Option<u32>,
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let key = if self.key.is_empty() {
+            "(root)"
+        } else {
+            &self.key
+        };
+        match self.line_number {
+            Some(line_number) => write!(f, "{} (line {}): {}", key, line_number, self.message),
+            None => write!(f, "{}: {}", key, self.message),
+        }
+    }
+}
+
+/// Validates the nodes in their json representation against a json schema
+///
+/// Line numbers are looked up by the dotted key of a violation, see
+/// `PropertyFileReader::line_numbers` and `YamlFileReader::line_numbers`
+pub fn validate(
+    nodes: &Nodes,
+    schema: &str,
+    line_numbers: &LinkedHashMap<String, u32>,
+) -> Result<Vec<Violation>, ProconError> {
+    let schema: serde_json::Value = serde_json::from_str(schema).map_err(|_| ProconError {
+        message: "Wrong json schema format".to_string(),
+    })?;
+    let compiled_schema = JSONSchema::compile(&schema).map_err(|error| ProconError {
+        message: ["Invalid json schema: ", &error.to_string()].concat(),
+    })?;
+    // the json crate writes the values, serde_json reads them for the validator
+    let instance: serde_json::Value =
+        serde_json::from_str(&to_json_value(nodes).dump()).map_err(|_| ProconError {
+            message: "Could not convert to json format".to_string(),
+        })?;
+
+    let mut violations: Vec<Violation> = vec![];
+    if let Err(errors) = compiled_schema.validate(&instance) {
+        for error in errors {
+            let key = dotted_key(error.instance_path.iter());
+            violations.push(Violation {
+                line_number: line_number(&key, line_numbers),
+                message: error.to_string(),
+                key,
+            });
+        }
+    }
+    Ok(violations)
+}
+
+fn dotted_key<'a>(path: impl Iterator<Item = &'a PathChunk>) -> String {
+    let mut key = String::new();
+    for chunk in path {
+        match chunk {
+            PathChunk::Property(name) => {
+                if !key.is_empty() {
+                    key.push('.');
+                }
+                key.push_str(name);
+            }
+            PathChunk::Index(index) => key.push_str(&["[", &index.to_string(), "]"].concat()),
+            PathChunk::Keyword(_) => {}
+        }
+    }
+    key
+}
+
+/// Line of the key itself, its first nested key or its closest parent
+fn line_number(key: &str, line_numbers: &LinkedHashMap<String, u32>) -> Option<u32> {
+    if let Some(line_number) = line_numbers.get(key) {
+        return Some(*line_number);
+    }
+    let nested = line_numbers.iter().find(|(other_key, _)| {
+        key.is_empty()
+            || other_key.starts_with(&[key, "."].concat())
+            || other_key.starts_with(&[key, "["].concat())
+    });
+    if let Some((_, line_number)) = nested {
+        return Some(*line_number);
+    }
+    let parent_end = key.rfind(['.', '['])?;
+    line_number(&key[..parent_end], line_numbers)
+}
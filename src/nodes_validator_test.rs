@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod tests {
+    use linked_hash_map::LinkedHashMap;
+
+    use crate::node::Node;
+    use crate::nodes::Nodes;
+    use crate::nodes_validator::{validate, Violation};
+    use crate::property_file_reader::PropertyFileReader;
+
+    const SCHEMA: &str = r#"{
+        "type": "object",
+        "properties": {
+            "server": {
+                "type": "object",
+                "properties": {
+                    "port": { "type": "integer" },
+                    "hosts": { "type": "array", "items": { "type": "string", "minLength": 2 } }
+                },
+                "required": ["port"]
+            }
+        }
+    }"#;
+
+    fn nodes(properties: &[(&str, &str)]) -> Nodes {
+        let mut nodes = Nodes::new();
+        for (key, value) in properties {
+            let mut node: Node = PropertyFileReader::property_to_node(key, value).unwrap();
+            nodes.merge(&mut node);
+        }
+        nodes
+    }
+
+    #[test]
+    fn validate_valid_nodes() {
+        let nodes = nodes(&[("server.port", "8080"), ("server.hosts", "alpha,beta")]);
+
+        let violations = validate(&nodes, SCHEMA, &LinkedHashMap::new()).unwrap();
+        assert!(violations.is_empty());
+    }
+
+    #[test]
+    fn validate_violations_with_line_numbers() {
+        let nodes = nodes(&[("server.port", "http"), ("server.hosts", "alpha,b")]);
+        let mut line_numbers = LinkedHashMap::new();
+        line_numbers.insert(String::from("server.port"), 1);
+        line_numbers.insert(String::from("server.hosts"), 2);
+
+        let violations = validate(&nodes, SCHEMA, &line_numbers).unwrap();
+        assert_eq!(2, violations.len());
+        let port = violations.iter().find(|v| v.key == "server.port").unwrap();
+        assert_eq!(Some(1), port.line_number);
+        // comma separated arrays share the line of their key
+        let host = violations
+            .iter()
+            .find(|v| v.key == "server.hosts[1]")
+            .unwrap();
+        assert_eq!(Some(2), host.line_number);
+    }
+
+    #[test]
+    fn validate_missing_key() {
+        let nodes = nodes(&[("server.hosts", "alpha,beta")]);
+
+        let violations = validate(&nodes, SCHEMA, &LinkedHashMap::new()).unwrap();
+        assert_eq!(1, violations.len());
+        assert_eq!("server", violations[0].key);
+        assert_eq!(None, violations[0].line_number);
+        assert_eq!(
+            "server: \"port\" is a required property",
+            violations[0].to_string()
+        );
+    }
+
+    #[test]
+    fn validate_invalid_schema() {
+        assert!(validate(&Nodes::new(), "{", &LinkedHashMap::new()).is_err());
+        assert!(validate(&Nodes::new(), r#"{"type": 1}"#, &LinkedHashMap::new()).is_err());
+    }
+
+    #[test]
+    fn violation_display() {
+        let violation = Violation {
+            key: String::new(),
+            message: String::from("message"),
+            line_number: Some(3),
+        };
+        assert_eq!("(root) (line 3): message", violation.to_string());
+    }
+}
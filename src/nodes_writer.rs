@@ -2,49 +2,103 @@ use std::fs::File;
 use std::io::Write;
 use std::path::PathBuf;
 
-use linked_hash_map::LinkedHashMap;
 use log::debug;
-use yaml_rust::{Yaml, YamlEmitter};
 
 use crate::args::{Args, TargetFormat};
+use crate::config_map::{data_key, manifest_style, object_name, Manifest};
 use crate::errors::ProconError;
-use crate::nodes::Nodes;
-
-#[cfg(test)]
-#[path = "./nodes_writer_test.rs"]
-mod nodes_writer_test;
+use crate::json_writer::{JsonStyle, JsonWriter};
+use crate::node::{
+    comment_line, is_nan_or_infinity, is_same_number, Node, NodeType, PropertiesStyle,
+};
+use crate::nodes::{Nodes, TagPolicy};
+use crate::yaml_writer::{YamlStyle, YamlWriter};
 
 pub fn to_yaml(args: &Args, nodes: &Nodes) -> Result<String, ProconError> {
-    let mut content = String::new();
-    let mut emitter = YamlEmitter::new(&mut content);
+    output_content(args, yaml_content(nodes, &yaml_style(args))?)
+}
 
-    // todo refactor this
-    let mut map: LinkedHashMap<Yaml, Yaml> = LinkedHashMap::new();
-    for node in nodes.iter() {
-        // root list treatment
-        if node.name.is_empty() {
-            let array: Yaml = node.into();
-            emitter.dump(&array).map_err(|_| ProconError {
-                message: "Could convert to yam format".to_string(),
-            })?;
-            break;
-        } else {
-            // the rest
-            map.insert(Yaml::from_str(&node.name), node.into());
-        }
+pub(crate) fn yaml_content(nodes: &Nodes, style: &YamlStyle) -> Result<String, ProconError> {
+    if nodes.iter().next().is_none() {
+        return Ok(String::new());
     }
+    Ok(YamlWriter::write(nodes, style))
+}
 
-    if !map.is_empty() {
-        let final_node = Yaml::Hash(map);
-        emitter.dump(&final_node).map_err(|_| ProconError {
-            message: "Could convert to yaml format".to_string(),
-        })?;
+/// Options of the yaml command, every other command writes the default style
+fn yaml_style(args: &Args) -> YamlStyle {
+    match &args.target_format {
+        TargetFormat::Yaml {
+            indent,
+            flow_arrays,
+            quote,
+            multiline,
+            no_document_marker,
+            ..
+        } => YamlStyle {
+            indent: usize::from(*indent),
+            flow_arrays: *flow_arrays,
+            quote: quote.clone(),
+            multiline: multiline.clone(),
+            document_marker: !no_document_marker,
+        },
+        _ => YamlStyle::default(),
     }
-
-    output_content(&args, content)
 }
 
 pub fn to_json(args: &Args, nodes: &Nodes) -> Result<String, ProconError> {
+    let nodes = &apply_tag_policy(nodes, &args.tagged_values)?;
+    check_json_numbers(nodes)?;
+    output_content(args, JsonWriter::write(nodes, &json_style(args)))
+}
+
+/// Json numbers have no nan and infinity, written as strings they would not read back as numbers
+fn check_json_numbers(nodes: &Nodes) -> Result<(), ProconError> {
+    for node in nodes.flat().iter() {
+        if let NodeType::NUMERIC(value) = &node.value {
+            if is_nan_or_infinity(value) {
+                return Err(ProconError {
+                    message: [
+                        "Value ",
+                        value,
+                        " of ",
+                        &node.name,
+                        " is not supported in json format",
+                    ]
+                    .concat(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Options of the json command
+fn json_style(args: &Args) -> JsonStyle {
+    match &args.target_format {
+        TargetFormat::Json {
+            jsonc,
+            indent,
+            compact,
+            sort_keys,
+            ascii,
+            flat,
+            ndjson,
+            ..
+        } => JsonStyle {
+            indent: usize::from(*indent),
+            compact: *compact,
+            sort_keys: *sort_keys,
+            ascii: *ascii,
+            flat: *flat,
+            ndjson: *ndjson,
+            comments: *jsonc,
+        },
+        _ => JsonStyle::default(),
+    }
+}
+
+pub(crate) fn to_json_value(nodes: &Nodes) -> json::JsonValue {
     let mut json_data = json::JsonValue::new_object();
     for node in nodes.iter() {
         // root list treatment
@@ -54,16 +108,354 @@ pub fn to_json(args: &Args, nodes: &Nodes) -> Result<String, ProconError> {
             json_data[node.name.clone()] = node.into();
         }
     }
-    output_content(&args, json_data.pretty(1))
+    json_data
+}
+
+pub fn to_toml(args: &Args, nodes: &Nodes) -> Result<String, ProconError> {
+    let nodes = &apply_tag_policy(nodes, &args.tagged_values)?;
+    output_content(args, toml_content(nodes)?)
+}
+
+pub(crate) fn toml_content(nodes: &Nodes) -> Result<String, ProconError> {
+    let mut table = toml::value::Table::new();
+    for node in nodes.iter() {
+        // root list treatment
+        if node.name.is_empty() {
+            return Err(ProconError {
+                message: "Root lists are not supported in toml format".to_string(),
+            });
+        }
+        check_toml_arrays(node, &node.name, false)?;
+        // toml has no null, keys with null values are left out
+        if node.value != NodeType::NULL {
+            table.insert(node.name.clone(), node.into());
+        }
+    }
+
+    toml::to_string(&toml::Value::Table(table)).map_err(|_| ProconError {
+        message: "Could not convert to toml format".to_string(),
+    })
+}
+
+/// Arrays toml can not represent, the serializer would write invalid toml for them
+///
+/// Tables in an array are only written as `[[key]]` sections if all elements are tables and
+/// the array is not an element itself, null elements would shift the following indices
+fn check_toml_arrays(node: &Node, key: &str, element: bool) -> Result<(), ProconError> {
+    match &node.value {
+        NodeType::ARRAY(elements) => {
+            if elements
+                .iter()
+                .any(|element| element.value == NodeType::NULL)
+            {
+                return Err(ProconError {
+                    message: [
+                        "Null element in array ",
+                        key,
+                        " is not supported in toml format",
+                    ]
+                    .concat(),
+                });
+            }
+            let tables = elements
+                .iter()
+                .filter(|element| element.value == NodeType::NONE)
+                .count();
+            if tables > 0 && (tables < elements.len() || element) {
+                return Err(ProconError {
+                    message: [
+                        "Array ",
+                        key,
+                        " mixes tables with other values or arrays, which is not supported in toml format",
+                    ]
+                    .concat(),
+                });
+            }
+            for (index, element) in elements.iter().enumerate() {
+                let element_key = [key, "[", &index.to_string(), "]"].concat();
+                check_toml_arrays(element, &element_key, true)?;
+            }
+        }
+        NodeType::NONE => {
+            for child in &node.children {
+                check_toml_arrays(child, &[key, ".", &child.name].concat(), false)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+impl From<&Node> for toml::Value {
+    fn from(node: &Node) -> Self {
+        match &node.value {
+            // toml has no null, keys with null values are left out
+            NodeType::NONE => {
+                let mut table = toml::value::Table::new();
+                for child in node
+                    .children
+                    .iter()
+                    .filter(|child| child.value != NodeType::NULL)
+                {
+                    table.insert(child.name.clone(), child.into());
+                }
+                toml::Value::Table(table)
+            }
+            NodeType::NULL => toml::Value::String(String::new()),
+            NodeType::BOOLEAN(value) => toml::Value::Boolean(*value),
+            NodeType::NUMERIC(value) => match value.parse::<i64>() {
+                Ok(parsed_value) => toml::Value::Integer(parsed_value),
+                Err(_) => match value.parse::<f64>() {
+                    Ok(parsed_value) if is_same_number(value, &parsed_value.to_string()) => {
+                        toml::Value::Float(parsed_value)
+                    }
+                    Ok(parsed_value) if !parsed_value.is_finite() => {
+                        toml::Value::Float(parsed_value)
+                    }
+                    // the number does not fit into a toml number, keep the value as string instead
+                    _ => toml::Value::String(value.clone()),
+                },
+            },
+            NodeType::STRING(value) => toml::Value::String(value.clone()),
+            NodeType::OBJECT(value) => toml::Value::String(value.clone()),
+            NodeType::ARRAY(value) => {
+                let mut array = vec![];
+                for element in value
+                    .iter()
+                    .filter(|element| element.value != NodeType::NULL)
+                {
+                    array.push(element.into());
+                }
+                toml::Value::Array(array)
+            }
+        }
+    }
 }
 
 pub fn to_properties(args: &Args, nodes: &Nodes) -> Result<String, ProconError> {
+    let nodes = &apply_tag_policy(nodes, &args.tagged_values)?;
+    output_content(args, properties_content(nodes, &properties_style(args)))
+}
+
+pub(crate) fn properties_content(nodes: &Nodes, style: &PropertiesStyle) -> String {
+    let mut sorted_nodes;
+    let nodes = match style.sort_keys {
+        true => {
+            sorted_nodes = nodes.clone();
+            sorted_nodes.sort();
+            &sorted_nodes
+        }
+        false => nodes,
+    };
+
     let mut string_content = "".to_string();
+    if let Some(header) = &style.header {
+        for line in header.lines() {
+            string_content.push_str(&comment_line(line, style));
+        }
+        string_content.push('\n');
+    }
+    for (index, node) in nodes.iter().enumerate() {
+        if style.separate_groups && index > 0 {
+            string_content.push('\n');
+        }
+        string_content.push_str(&node.to_properties_with_style(style));
+    }
+    for comment in &nodes.trailing_comments {
+        string_content.push_str(&comment_line(comment, style));
+    }
+    string_content
+}
+
+/// Options of the properties command, every other command writes plain properties
+fn properties_style(args: &Args) -> PropertiesStyle {
+    match &args.target_format {
+        TargetFormat::Properties {
+            indexed_arrays,
+            escape_unicode,
+            line_width,
+            output_delimiter,
+            delimiter_spacing,
+            sort_keys,
+            separate_groups,
+            header,
+            ..
+        } => PropertiesStyle {
+            indexed_arrays: *indexed_arrays,
+            escape_unicode: *escape_unicode,
+            line_width: *line_width,
+            delimiter: output_delimiter.clone(),
+            delimiter_spacing: delimiter_spacing.clone(),
+            sort_keys: *sort_keys,
+            separate_groups: *separate_groups,
+            header: header.clone(),
+        },
+        _ => PropertiesStyle::default(),
+    }
+}
+
+pub fn to_dotenv(args: &Args, nodes: &Nodes) -> Result<String, ProconError> {
+    let nodes = &apply_tag_policy(nodes, &args.tagged_values)?;
+    let mut content = String::new();
     for node in nodes.iter() {
-        let content: String = node.into();
-        string_content.push_str(&content);
+        // root list treatment
+        if node.name.is_empty() {
+            return Err(ProconError {
+                message: "Root lists are not supported in dotenv format".to_string(),
+            });
+        }
+        for (key, value) in node.flatten() {
+            let variable = env_variable(&key);
+            let value = env_value(&variable, &value)?;
+            content.push_str(&[&variable, "=", &value, "\n"].concat());
+        }
+    }
+    output_content(args, content)
+}
+
+pub fn to_config_map(args: &Args, nodes: &Nodes) -> Result<String, ProconError> {
+    let TargetFormat::ConfigMap {
+        name,
+        namespace,
+        labels,
+        secret,
+        flat,
+        embed,
+        file,
+        ..
+    } = &args.target_format
+    else {
+        return Err(ProconError {
+            message: "Missing configmap options".to_string(),
+        });
+    };
+
+    let file_stem = match file.to_str() {
+        Some("-") => "application",
+        _ => file
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default(),
+    };
+    let data: Vec<(String, String)> = if *flat {
+        let mut data = vec![];
+        for node in apply_tag_policy(nodes, &args.tagged_values)?.iter() {
+            for (key, value) in node.flatten() {
+                data.push((data_key(&key), value));
+            }
+        }
+        data
+    } else {
+        // one embedded file like application.properties
+        let content = match embed.as_str() {
+            "yaml" => yaml_content(nodes, &YamlStyle::default())?,
+            _ => properties_content(
+                &apply_tag_policy(nodes, &args.tagged_values)?,
+                &PropertiesStyle::default(),
+            ),
+        };
+        vec![([file_stem, ".", embed].concat(), content)]
+    };
+
+    let manifest = Manifest {
+        name: &object_name(name.as_deref().unwrap_or(file_stem)),
+        namespace: namespace.as_deref(),
+        labels,
+        secret: *secret,
+    };
+    output_content(
+        args,
+        yaml_content(&manifest.to_nodes(&data)?, &manifest_style())?,
+    )
+}
+
+/// Yaml tagged values as configured for formats without tags
+pub(crate) fn apply_tag_policy(
+    nodes: &Nodes,
+    tag_policy: &TagPolicy,
+) -> Result<Nodes, ProconError> {
+    let mut nodes = nodes.clone();
+    for node in nodes.iter_mut() {
+        let key = node.name.clone();
+        apply_tag_policy_to_node(node, &key, tag_policy)?;
+    }
+    Ok(nodes)
+}
+
+fn apply_tag_policy_to_node(
+    node: &mut Node,
+    key: &str,
+    tag_policy: &TagPolicy,
+) -> Result<(), ProconError> {
+    if let Some(tag) = &node.tag {
+        match tag_policy {
+            TagPolicy::Keep => node.tag = None,
+            TagPolicy::Stringify => {
+                node.value = NodeType::STRING(YamlWriter::flow(node));
+                node.children = vec![];
+                node.tag = None;
+                return Ok(());
+            }
+            TagPolicy::Error => {
+                return Err(ProconError {
+                    message: ["Tagged value ", tag, " in ", key].concat(),
+                })
+            }
+        }
+    }
+    if let NodeType::ARRAY(elements) = &mut node.value {
+        for (index, element) in elements.iter_mut().enumerate() {
+            let element_key = [key, "[", &index.to_string(), "]"].concat();
+            apply_tag_policy_to_node(element, &element_key, tag_policy)?;
+        }
+    }
+    for child in &mut node.children {
+        let child_key = if key.is_empty() {
+            child.name.clone()
+        } else {
+            [key, ".", &child.name].concat()
+        };
+        apply_tag_policy_to_node(child, &child_key, tag_policy)?;
+    }
+    Ok(())
+}
+
+/// Relaxed binding of a key, `servers[0].host-name` becomes `SERVERS_0_HOST_NAME`
+pub(crate) fn env_variable(key: &str) -> String {
+    key.replace(['.', '-', '['], "_")
+        .replace(']', "")
+        .to_uppercase()
+}
+
+/// Values are written as they are, `docker run --env-file` takes everything after the `=`
+/// literally including quotes and does not expand `$`
+///
+/// Values the dotenv reader would read differently are rejected: line breaks, surrounding
+/// whitespace, leading or trailing quotes and inline comments
+pub(crate) fn env_value(variable: &str, value: &str) -> Result<String, ProconError> {
+    let unsupported = |reason: &str| ProconError {
+        message: [
+            "Value of ",
+            variable,
+            " ",
+            reason,
+            ", which is not supported in dotenv format",
+        ]
+        .concat(),
+    };
+    if value.contains(['\n', '\r']) {
+        return Err(unsupported("contains a line break"));
+    }
+    if value.trim() != value {
+        return Err(unsupported("starts or ends with whitespace"));
+    }
+    if value.starts_with(['"', '\'']) || value.ends_with(['"', '\'']) {
+        return Err(unsupported("starts or ends with a quote"));
+    }
+    if value.contains(" #") {
+        return Err(unsupported("contains an inline comment"));
     }
-    output_content(&args, string_content)
+    Ok(value.to_string())
 }
 
 fn output_content(args: &Args, content: String) -> Result<String, ProconError> {
@@ -71,7 +463,7 @@ fn output_content(args: &Args, content: String) -> Result<String, ProconError> {
     if args.dry_run {
         Ok(String::from("Print converted format to console"))
     } else {
-        let output_filename = determine_output_filename(&args);
+        let output_filename = determine_output_filename(args);
         let mut output_file: File = File::create(&output_filename).map_err(|_| ProconError {
             message: "Could not create file".to_string(),
         })?;
@@ -80,7 +472,7 @@ fn output_content(args: &Args, content: String) -> Result<String, ProconError> {
         })?;
 
         let mut message = "Converted ".to_string();
-        message.push_str(&args.target_format.path_buf().to_str().unwrap());
+        message.push_str(args.target_format.path_buf().to_str().unwrap());
         message.push_str(" to ");
         message.push_str(&output_filename);
         Ok(message)
@@ -89,9 +481,13 @@ fn output_content(args: &Args, content: String) -> Result<String, ProconError> {
 
 pub(crate) fn determine_output_filename(args: &Args) -> String {
     let output_filename: String;
-    if args.output_filename.is_some() {
-        output_filename = args.output_filename.as_ref().unwrap().to_string();
+    if let Some(filename) = &args.output_filename {
+        output_filename = filename.to_string();
         debug!("User provided output filename {}", output_filename);
+    } else if let TargetFormat::Set { file, .. } = &args.target_format {
+        // set changes the input file itself
+        output_filename = file.to_str().unwrap().to_string();
+        debug!("Write back to input file {}", output_filename);
     } else {
         output_filename = default_filename(&args.target_format);
         debug!("User default output filename {}", output_filename);
@@ -104,6 +500,19 @@ pub(crate) fn default_filename(command: &TargetFormat) -> String {
         TargetFormat::Properties { file, .. } => (file, "properties".to_string()),
         TargetFormat::Json { file, .. } => (file, "json".to_string()),
         TargetFormat::Yaml { file, .. } => (file, "yaml".to_string()),
+        TargetFormat::Toml { file, .. } => (file, "toml".to_string()),
+        TargetFormat::Dotenv { file, .. } => (file, "env".to_string()),
+        // keeps yaml input files from being overwritten
+        TargetFormat::ConfigMap { file, .. } => (file, "configmap.yaml".to_string()),
+        TargetFormat::Diff { file, .. } => (file, "diff".to_string()),
+        TargetFormat::Validate { file, .. } => (file, "json".to_string()),
+        TargetFormat::Get { file, .. } | TargetFormat::Set { file, .. } => (
+            file,
+            file.extension()
+                .and_then(|extension| extension.to_str())
+                .unwrap_or_default()
+                .to_string(),
+        ),
     };
     let mut filename = path_buf.file_stem().unwrap().to_str().unwrap();
 
@@ -111,5 +520,5 @@ pub(crate) fn default_filename(command: &TargetFormat) -> String {
     if path_buf == &PathBuf::from("-") {
         filename = "stdin";
     }
-    return [filename, ".", extension.to_string().to_lowercase().as_str()].concat();
+    [filename, ".", extension.to_string().to_lowercase().as_str()].concat()
 }
@@ -1,18 +1,33 @@
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
     use std::path::PathBuf;
 
     use clap_verbosity_flag::Verbosity;
 
     use crate::args::{Args, TargetFormat};
-    use crate::nodes_writer::{default_filename, determine_output_filename};
-    use crate::property_file_reader::Delimiter;
+    use crate::node::PropertiesStyle;
+    use crate::nodes::Nodes;
+    use crate::nodes::{ArrayMerge, DelimiterSpacing, MultilineStyle, QuoteStyle, TagPolicy};
+    use crate::nodes_writer::{
+        apply_tag_policy, default_filename, determine_output_filename, env_value, env_variable,
+        properties_content, toml_content,
+    };
+    use crate::property_file_reader::{Delimiter, PropertyFileReader};
+    use crate::toml_file_reader::TomlFileReader;
+    use crate::yaml_file_reader::YamlFileReader;
 
     #[test]
     fn default_filename_multiple_extensions() {
         let command = TargetFormat::Yaml {
-            property_delimiter: Delimiter::Equals,
+            property_delimiter: Some(Delimiter::Equals),
+            indent: 2,
+            flow_arrays: None,
+            quote: QuoteStyle::Ambiguous,
+            multiline: MultilineStyle::Quoted,
+            no_document_marker: false,
             file: PathBuf::from("test.properties.properties"),
+            merge_files: vec![],
         };
         assert_eq!("test.properties.yaml", default_filename(&command));
     }
@@ -20,8 +35,14 @@ mod tests {
     #[test]
     fn default_filename_multiple_dots_input_name() {
         let command = TargetFormat::Yaml {
-            property_delimiter: Delimiter::Equals,
+            property_delimiter: Some(Delimiter::Equals),
+            indent: 2,
+            flow_arrays: None,
+            quote: QuoteStyle::Ambiguous,
+            multiline: MultilineStyle::Quoted,
+            no_document_marker: false,
             file: PathBuf::from("test.test2.test3.properties"),
+            merge_files: vec![],
         };
         assert_eq!("test.test2.test3.yaml", default_filename(&command));
     }
@@ -29,8 +50,14 @@ mod tests {
     #[test]
     fn default_filename_yaml() {
         let command = TargetFormat::Yaml {
-            property_delimiter: Delimiter::Equals,
+            property_delimiter: Some(Delimiter::Equals),
+            indent: 2,
+            flow_arrays: None,
+            quote: QuoteStyle::Ambiguous,
+            multiline: MultilineStyle::Quoted,
+            no_document_marker: false,
             file: PathBuf::from("test.properties"),
+            merge_files: vec![],
         };
         assert_eq!("test.yaml", default_filename(&command));
     }
@@ -38,17 +65,92 @@ mod tests {
     #[test]
     fn default_filename_json() {
         let command = TargetFormat::Json {
-            property_delimiter: Delimiter::Equals,
+            property_delimiter: Some(Delimiter::Equals),
+            jsonc: false,
+            indent: 1,
+            compact: false,
+            sort_keys: false,
+            ascii: false,
+            flat: false,
+            ndjson: false,
             file: PathBuf::from("test.properties"),
+            merge_files: vec![],
         };
         assert_eq!("test.json", default_filename(&command));
     }
 
+    #[test]
+    fn default_filename_toml() {
+        let command = TargetFormat::Toml {
+            property_delimiter: Some(Delimiter::Equals),
+            file: PathBuf::from("test.properties"),
+            merge_files: vec![],
+        };
+        assert_eq!("test.toml", default_filename(&command));
+    }
+
+    #[test]
+    fn default_filename_dotenv() {
+        let command = TargetFormat::Dotenv {
+            property_delimiter: Some(Delimiter::Equals),
+            file: PathBuf::from("test.properties"),
+            merge_files: vec![],
+        };
+        assert_eq!("test.env", default_filename(&command));
+    }
+
+    #[test]
+    fn dotenv_relaxed_binding() {
+        assert_eq!("SERVER_PORT", env_variable("server.port"));
+        assert_eq!("SERVERS_0_HOST_NAME", env_variable("servers[0].host-name"));
+        assert_eq!("MATRIX_0_1", env_variable("matrix[0][1]"));
+    }
+
+    #[test]
+    fn dotenv_unquoted_values() {
+        assert_eq!("8080", env_value("PORT", "8080").unwrap());
+        assert_eq!(
+            "jdbc:h2:mem/db",
+            env_value("URL", "jdbc:h2:mem/db").unwrap()
+        );
+        assert_eq!("", env_value("EMPTY", "").unwrap());
+        assert_eq!("two words", env_value("TEXT", "two words").unwrap());
+        assert_eq!("pa$$word", env_value("PASSWORD", "pa$$word").unwrap());
+        assert_eq!(
+            "say \"hi\" now",
+            env_value("TEXT", "say \"hi\" now").unwrap()
+        );
+        assert_eq!("issue#1", env_value("TEXT", "issue#1").unwrap());
+        assert_eq!(
+            "Value of TEXT contains a line break, which is not supported in dotenv format",
+            env_value("TEXT", "hi\nbye").unwrap_err().message
+        );
+        for value in [
+            "\"quoted\"",
+            "say \"hi\"",
+            "'single'",
+            "say \"hi\" #1",
+            " a",
+            "a ",
+        ] {
+            assert!(env_value("TEXT", value).is_err(), "{}", value);
+        }
+    }
+
     #[test]
     fn default_filename_properties() {
         let command = TargetFormat::Properties {
-            property_delimiter: Delimiter::Equals,
+            property_delimiter: Some(Delimiter::Equals),
+            indexed_arrays: false,
+            escape_unicode: false,
+            line_width: None,
+            output_delimiter: Delimiter::Equals,
+            delimiter_spacing: DelimiterSpacing::None,
+            sort_keys: false,
+            separate_groups: false,
+            header: None,
             file: PathBuf::from("test.yaml"),
+            merge_files: vec![],
         };
         assert_eq!("test.properties", default_filename(&command));
     }
@@ -57,13 +159,33 @@ mod tests {
     fn determine_default_filename_default() {
         let args: Args = Args {
             target_format: TargetFormat::Properties {
-                property_delimiter: Delimiter::Equals,
+                property_delimiter: Some(Delimiter::Equals),
+                indexed_arrays: false,
+                escape_unicode: false,
+                line_width: None,
+                output_delimiter: Delimiter::Equals,
+                delimiter_spacing: DelimiterSpacing::None,
+                sort_keys: false,
+                separate_groups: false,
+                header: None,
                 file: PathBuf::from("filename.properties"),
+                merge_files: vec![],
             },
             dry_run: false,
             from_property_file: false,
             from_yaml_file: false,
             from_json_file: false,
+            from_toml_file: false,
+            from_dotenv_file: false,
+            strict_numbers: false,
+            string_keys: vec![],
+            interpolate: false,
+            array_merge: ArrayMerge::Replace,
+            tagged_values: TagPolicy::Keep,
+            select: None,
+            document: None,
+            profile: None,
+            split: false,
             output_filename: None,
             verbose: Verbosity::new(0, 0),
         };
@@ -77,13 +199,33 @@ mod tests {
         let cli_output_file: String = "test.yaml".to_string();
         let args: Args = Args {
             target_format: TargetFormat::Properties {
-                property_delimiter: Delimiter::Equals,
+                property_delimiter: Some(Delimiter::Equals),
+                indexed_arrays: false,
+                escape_unicode: false,
+                line_width: None,
+                output_delimiter: Delimiter::Equals,
+                delimiter_spacing: DelimiterSpacing::None,
+                sort_keys: false,
+                separate_groups: false,
+                header: None,
                 file: PathBuf::from("filename.properties"),
+                merge_files: vec![],
             },
             dry_run: false,
             from_property_file: false,
             from_yaml_file: false,
             from_json_file: false,
+            from_toml_file: false,
+            from_dotenv_file: false,
+            strict_numbers: false,
+            string_keys: vec![],
+            interpolate: false,
+            array_merge: ArrayMerge::Replace,
+            tagged_values: TagPolicy::Keep,
+            select: None,
+            document: None,
+            profile: None,
+            split: false,
             output_filename: Some(cli_output_file.to_string()),
             verbose: Verbosity::new(0, 0),
         };
@@ -91,4 +233,159 @@ mod tests {
         let file: String = determine_output_filename(&args);
         assert_eq!(cli_output_file, file);
     }
+
+    #[test]
+    fn tag_policy() {
+        let yaml_data: serde_yaml::Value =
+            serde_yaml::from_str("name: !Ref Bucket\narn: !GetAtt [Bucket, Arn]").unwrap();
+        let nodes = YamlFileReader::convert_yaml_values_to_nodes(&yaml_data).unwrap();
+
+        let kept = apply_tag_policy(&nodes, &TagPolicy::Keep).unwrap();
+        assert_eq!(None, kept.find("name").unwrap().tag);
+        assert_eq!("Bucket", kept.find("name").unwrap().value.to_string());
+        assert_eq!("Arn", kept.find("arn[1]").unwrap().value.to_string());
+
+        let stringified = apply_tag_policy(&nodes, &TagPolicy::Stringify).unwrap();
+        assert_eq!(
+            "!Ref Bucket",
+            stringified.find("name").unwrap().value.to_string()
+        );
+        assert_eq!(
+            "!GetAtt [Bucket, Arn]",
+            stringified.find("arn").unwrap().value.to_string()
+        );
+
+        let error = apply_tag_policy(&nodes, &TagPolicy::Error).unwrap_err();
+        assert_eq!("Tagged value !Ref in name", error.message);
+    }
+
+    #[test]
+    fn properties_content_style() {
+        let yaml_data: serde_yaml::Value = serde_yaml::from_str(
+            "spring:\n  name: demo\n  active: true\nserver:\n  port: 8080\n  address: localhost",
+        )
+        .unwrap();
+        let nodes = YamlFileReader::convert_yaml_values_to_nodes(&yaml_data).unwrap();
+
+        let style = PropertiesStyle {
+            delimiter: Delimiter::Colon,
+            delimiter_spacing: DelimiterSpacing::After,
+            sort_keys: true,
+            separate_groups: true,
+            header: Some("Generated file\ndo not edit".to_string()),
+            ..PropertiesStyle::default()
+        };
+        assert_eq!(
+            "# Generated file\n# do not edit\n\n\
+            server.address: localhost\nserver.port: 8080\n\n\
+            spring.active: true\nspring.name: demo\n",
+            properties_content(&nodes, &style)
+        );
+
+        let style = PropertiesStyle {
+            delimiter_spacing: DelimiterSpacing::Both,
+            ..PropertiesStyle::default()
+        };
+        assert_eq!(
+            "spring.name = demo\nspring.active = true\nserver.port = 8080\nserver.address = localhost\n",
+            properties_content(&nodes, &style)
+        );
+
+        // spacing is not used for the whitespace delimiter
+        let style = PropertiesStyle {
+            delimiter: Delimiter::Whitespace,
+            delimiter_spacing: DelimiterSpacing::Both,
+            ..PropertiesStyle::default()
+        };
+        assert!(properties_content(&nodes, &style).starts_with("spring.name demo\n"));
+    }
+
+    /// Values of the toml content read back, toml writes tables after the other values
+    fn toml_round_trip(nodes: &Nodes) -> BTreeMap<String, String> {
+        let content = toml_content(nodes).unwrap();
+        let toml_value: toml::Value = toml::from_str(&content).unwrap();
+        let read_back = TomlFileReader::convert_toml_values_to_nodes(&toml_value).unwrap();
+        read_back.flatten().into_iter().collect()
+    }
+
+    #[test]
+    fn toml_content_round_trip() {
+        let yaml_data: serde_yaml::Value = serde_yaml::from_str(
+            "name: demo
+servers:
+  - host: a
+    ports: [80, 443]
+    tls:
+      enabled: true
+  - host: b
+matrix: [[1, 2], [a]]
+mixed: [1, a]
+",
+        )
+        .unwrap();
+        let nodes = YamlFileReader::convert_yaml_values_to_nodes(&yaml_data).unwrap();
+
+        let values: BTreeMap<String, String> = nodes.flatten().into_iter().collect();
+        assert_eq!(values, toml_round_trip(&nodes));
+    }
+
+    #[test]
+    fn toml_content_float_round_trip() {
+        let toml_data: toml::Value = toml::from_str(
+            "count = 3
+ratio = 3.0
+exp = 1e5
+precise = 1.78
+limit = -inf
+missing = nan
+",
+        )
+        .unwrap();
+        let nodes = TomlFileReader::convert_toml_values_to_nodes(&toml_data).unwrap();
+
+        let content = toml_content(&nodes).unwrap();
+        let read_back: toml::Value = toml::from_str(&content).unwrap();
+        assert_eq!(Some(&toml::Value::Integer(3)), read_back.get("count"));
+        assert_eq!(Some(&toml::Value::Float(3.0)), read_back.get("ratio"));
+        assert_eq!(Some(&toml::Value::Float(100000.0)), read_back.get("exp"));
+        assert_eq!(Some(&toml::Value::Float(1.78)), read_back.get("precise"));
+        assert_eq!(
+            Some(&toml::Value::Float(f64::NEG_INFINITY)),
+            read_back.get("limit")
+        );
+        assert!(read_back["missing"].as_float().unwrap().is_nan());
+
+        let values: BTreeMap<String, String> = nodes.flatten().into_iter().collect();
+        assert_eq!(values, toml_round_trip(&nodes));
+    }
+
+    #[test]
+    fn toml_content_unsupported_arrays() {
+        let mut nodes = Nodes::new();
+        for (key, value) in [("list[0]", "x"), ("list[1].name", "y")] {
+            nodes.merge(&mut PropertyFileReader::property_to_node(key, value).unwrap());
+        }
+        let error = toml_content(&nodes).unwrap_err();
+        assert_eq!(
+            "Array list mixes tables with other values or arrays, which is not supported in toml format",
+            error.message
+        );
+
+        let yaml_data: serde_yaml::Value = serde_yaml::from_str("nested: [[{name: a}]]").unwrap();
+        let nodes = YamlFileReader::convert_yaml_values_to_nodes(&yaml_data).unwrap();
+        let error = toml_content(&nodes).unwrap_err();
+        assert_eq!(
+            "Array nested[0] mixes tables with other values or arrays, which is not supported in toml format",
+            error.message
+        );
+        let nodes = YamlFileReader::convert_yaml_values_to_nodes(
+            &serde_yaml::from_str("nulls: [1, ~, 3]").unwrap(),
+        )
+        .unwrap();
+        let error = toml_content(&nodes).unwrap_err();
+        assert_eq!(
+            "Null element in array nulls is not supported in toml format",
+            error.message
+        );
+    }
 }
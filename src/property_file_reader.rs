@@ -1,5 +1,8 @@
-use std::{collections::HashMap, fmt::Display, str::FromStr};
+use std::iter::Peekable;
+use std::str::Chars;
+use std::{fmt::Display, str::FromStr};
 
+use linked_hash_map::LinkedHashMap;
 use log::{debug, info, trace};
 
 use crate::args::Args;
@@ -12,8 +15,9 @@ use crate::nodes::Nodes;
 #[path = "./property_file_reader_test.rs"]
 mod property_file_reader_test;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub enum Delimiter {
+    #[default]
     Equals,
     Colon,
     Whitespace,
@@ -26,13 +30,31 @@ impl Display for Delimiter {
 }
 
 impl Delimiter {
-    fn value(&self) -> char {
+    pub(crate) fn value(&self) -> char {
         match self {
             Delimiter::Equals => '=',
             Delimiter::Colon => ':',
             Delimiter::Whitespace => ' ',
         }
     }
+
+    /// Whitespace delimiters also include tabs and form feeds
+    fn separates(&self, character: char) -> bool {
+        match self {
+            Delimiter::Whitespace => matches!(character, ' ' | '\t' | '\x0c'),
+            _ => character == self.value(),
+        }
+    }
+
+    /// Without an explicit delimiter the first `=`, `:` or whitespace ends the key
+    fn is_separator(delimiter: Option<&Delimiter>, character: char) -> bool {
+        match delimiter {
+            Some(delimiter) => delimiter.separates(character),
+            None => [Delimiter::Equals, Delimiter::Colon, Delimiter::Whitespace]
+                .iter()
+                .any(|delimiter| delimiter.separates(character)),
+        }
+    }
 }
 
 impl FromStr for Delimiter {
@@ -48,142 +70,338 @@ impl FromStr for Delimiter {
     }
 }
 
+#[derive(Debug)]
+pub(crate) enum KeySegment<'a> {
+    Name(&'a str),
+    Index(usize),
+}
+
 #[derive(Debug)]
 pub struct PropertyFileReader {
-    pub(crate) content: HashMap<String, Line>,
-    pub(crate) last_key: String,
+    /// parsed lines in the order of their (last) occurrence in the file
+    pub(crate) content: LinkedHashMap<String, Line>,
+    /// logical line continued on the next line and the number of its first line
+    continued_line: Option<(String, u32)>,
+    /// comment lines above a key by the key
+    pub(crate) comments: LinkedHashMap<String, Vec<String>>,
+    /// comment lines not yet followed by a key
+    pending_comments: Vec<String>,
 }
 
 // todo check if lib is available for this. dotproperties crate?
 #[allow(dead_code)]
 impl PropertyFileReader {
-    pub fn parse(args: &Args, content: &String) -> Result<Nodes, ProconError> {
+    pub fn parse(args: &Args, content: &str) -> Result<Nodes, ProconError> {
         info!("Use PropertyFileReader");
-        let config_file = Self::read_lines(args, &content);
+        let config_file = Self::read_lines(args, content)?;
         Self::convert_property_to_nodes(&config_file)
     }
+    /// Line numbers of all keys, the last occurrence wins for duplicated keys
+    pub fn line_numbers(args: &Args, content: &str) -> LinkedHashMap<String, u32> {
+        let Ok(config_file) = Self::read_lines(args, content) else {
+            return LinkedHashMap::new();
+        };
+        config_file
+            .content
+            .iter()
+            .map(|(key, line)| (key.clone(), line.line_number))
+            .collect()
+    }
+
     fn convert_property_to_nodes(config_file: &PropertyFileReader) -> Result<Nodes, ProconError> {
         let mut yaml_nodes: Nodes = Nodes::new();
         for (prop_key, line) in config_file.content.iter() {
-            let mut node_parts = prop_key.split(".").collect::<Vec<&str>>();
-            trace!("Node parts: {:?}", node_parts);
-            if node_parts.is_empty() {
-                trace!("Ignore empty parts");
-                continue;
+            if let Some(mut new_node) = Self::property_to_node(prop_key, &line.value) {
+                yaml_nodes.merge(&mut new_node);
+            }
+        }
+        for (prop_key, comments) in config_file.comments.iter() {
+            if let Some(node) = yaml_nodes.find_mut(prop_key) {
+                node.comments = comments.clone();
             }
+        }
+        // comments below the last key are never followed by a key
+        yaml_nodes.trailing_comments = config_file.pending_comments.clone();
 
-            let name = node_parts[0];
-            node_parts.remove(0);
-            let mut new_node = Node::new_from_name(name);
+        Ok(yaml_nodes)
+    }
 
-            // case key has no sub nodes
-            if node_parts.len() == 0 {
-                new_node.value = NodeType::parse(&line.value);
-                yaml_nodes.merge(&mut new_node);
-                continue;
+    /// Root node with the nested nodes of a single property like `servers[0].host=localhost`
+    pub fn property_to_node(key: &str, value: &str) -> Option<Node> {
+        let mut key_segments = Self::key_segments(key);
+        trace!("Key segments: {:?}", key_segments);
+        if key_segments.is_empty() {
+            trace!("Ignore empty parts");
+            return None;
+        }
+
+        // keys starting with an index describe a root list
+        let name = match key_segments[0] {
+            KeySegment::Name(name) => {
+                key_segments.remove(0);
+                name
             }
+            KeySegment::Index(_) => "",
+        };
+        let mut new_node = Node::new_from_name(name);
+        Self::create_nodes_from_segments(&mut new_node, &key_segments, value);
+        Some(new_node)
+    }
 
-            // create children
-            Self::create_child_nodes(&mut new_node, &mut node_parts, &line.value);
-            yaml_nodes.merge(&mut new_node);
+    /// Splits a key like `servers[0].host` into its names and array indices
+    pub(crate) fn key_segments(key: &str) -> Vec<KeySegment<'_>> {
+        let mut segments: Vec<KeySegment> = vec![];
+        for part in key.split('.') {
+            match Self::indexed_part(part) {
+                Some((name, indices)) => {
+                    if !name.is_empty() {
+                        segments.push(KeySegment::Name(name));
+                    }
+                    segments.extend(indices.into_iter().map(KeySegment::Index));
+                }
+                // not a valid index notation, keep the part as it is
+                None => segments.push(KeySegment::Name(part)),
+            }
         }
+        segments
+    }
 
-        Ok(yaml_nodes)
+    fn indexed_part(part: &str) -> Option<(&str, Vec<usize>)> {
+        let (name, mut rest) = part.split_once('[')?;
+        let mut indices: Vec<usize> = vec![];
+        loop {
+            let (index, remaining) = rest.split_once(']')?;
+            indices.push(index.parse::<usize>().ok()?);
+            if remaining.is_empty() {
+                return Some((name, indices));
+            }
+            rest = remaining.strip_prefix('[')?;
+        }
     }
 
-    fn read_lines(args: &Args, content: &String) -> PropertyFileReader {
+    fn read_lines(args: &Args, content: &str) -> Result<PropertyFileReader, ProconError> {
         let mut config_file = PropertyFileReader::new();
-        let mut line_number = 1;
-        let delimiter = &args.target_format.delimiter();
-        for line in content.split("\n") {
-            config_file.process_line(line, line_number, &delimiter.unwrap());
-            line_number = line_number + 1;
+        let delimiter = args.target_format.delimiter();
+        for (index, line) in content.split('\n').enumerate() {
+            config_file.process_line(line, index as u32 + 1, delimiter)?;
         }
-        config_file
+        config_file.finish(delimiter)?;
+        Ok(config_file)
     }
     pub fn create_child_nodes(node: &mut Node, parts: &mut Vec<&str>, value: &str) {
-        let mut last_node = &mut *node;
-        for (index, name) in parts.iter().enumerate() {
-            let mut new_node = Node::new_child(index + 1, last_node, name);
-            if index == parts.len() - 1 {
-                new_node.value = NodeType::parse(value);
-            }
+        let key_segments: Vec<KeySegment> =
+            parts.iter().map(|part| KeySegment::Name(part)).collect();
+        Self::create_nodes_from_segments(node, &key_segments, value);
+    }
 
-            let children = &mut last_node.children;
-            children.push(new_node.clone());
-            last_node = &mut children[0];
+    fn create_nodes_from_segments(node: &mut Node, key_segments: &[KeySegment], value: &str) {
+        match key_segments.first() {
+            None => node.value = NodeType::parse(value),
+            Some(KeySegment::Name(name)) => {
+                let mut new_node = Node::new_child(node.level + 1, node, name);
+                Self::create_nodes_from_segments(&mut new_node, &key_segments[1..], value);
+                node.children.push(new_node);
+            }
+            Some(KeySegment::Index(index)) => {
+                let mut element = Node::new_child(node.level + 1, node, &index.to_string());
+                match key_segments.len() {
+                    // indexed elements hold a single value, commas are part of the value
+                    1 => element.value = NodeType::parse_scalar(value),
+                    _ => Self::create_nodes_from_segments(&mut element, &key_segments[1..], value),
+                }
+                node.value = NodeType::ARRAY(vec![element]);
+            }
         }
     }
 
     fn new() -> PropertyFileReader {
         PropertyFileReader {
-            content: HashMap::new(),
-            last_key: String::from(""),
+            content: LinkedHashMap::new(),
+            continued_line: None,
+            comments: LinkedHashMap::new(),
+            pending_comments: Vec::new(),
         }
     }
 
-    fn process_line(&mut self, line: &str, line_number: u32, delimiter: &Delimiter) {
-        // case empty lines
-        if line.is_empty() {
-            return;
+    /// Processes a natural line of the file, lines ending with an odd number of `\` continue
+    /// on the next line without its leading whitespace
+    fn process_line(
+        &mut self,
+        line: &str,
+        line_number: u32,
+        delimiter: Option<&Delimiter>,
+    ) -> Result<(), ProconError> {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let (logical_line, line_number) = match self.continued_line.take() {
+            Some((previous, first_line_number)) => (
+                [previous.as_str(), line.trim_start()].concat(),
+                first_line_number,
+            ),
+            None => {
+                let line = line.trim_start();
+                // case empty lines
+                if line.is_empty() {
+                    return Ok(());
+                }
+                // comments only start at the beginning of a logical line
+                if line.starts_with('#') || line.starts_with('!') {
+                    self.pending_comments.push(Self::comment_text(line));
+                    return Ok(());
+                }
+                (line.to_string(), line_number)
+            }
+        };
+
+        if self.is_multiline(&logical_line) {
+            debug!("'{}' is a multiline", logical_line);
+            let without_backslash = &logical_line[..logical_line.len() - 1];
+            self.continued_line = Some((without_backslash.to_string(), line_number));
+            return Ok(());
         }
-        // case comments consider multiline here
-        if !self.consider_multiline() && line.starts_with("#") || line.starts_with("!") {
-            return;
+        self.process_logical_line(&logical_line, line_number, delimiter)
+    }
+
+    /// Processes a line continued on the last line of the file
+    fn finish(&mut self, delimiter: Option<&Delimiter>) -> Result<(), ProconError> {
+        match self.continued_line.take() {
+            Some((line, line_number)) => self.process_logical_line(&line, line_number, delimiter),
+            None => Ok(()),
         }
+    }
 
-        // only support one delimiter per file
-        let parts = line.split_once(delimiter.value());
-        // get value pop gets the last value
-        let (key, value) = match parts {
-            None => ("", ""),
-            Some(key_value) => key_value,
-        };
+    /// The key ends at the first unescaped delimiter, whitespace around the delimiter is ignored
+    ///
+    /// Like `java.util.Properties` the delimiter is detected per line unless one is given
+    fn process_logical_line(
+        &mut self,
+        line: &str,
+        line_number: u32,
+        delimiter: Option<&Delimiter>,
+    ) -> Result<(), ProconError> {
+        let mut characters = line.chars().peekable();
+        let mut key = String::new();
+        // length of the key without unescaped trailing whitespace
+        let mut key_length = 0;
+        let mut separator: Option<char> = None;
+        while let Some(character) = characters.next() {
+            if character == '\\' {
+                key.push_str(&Self::unescape(&mut characters, line_number)?);
+                key_length = key.len();
+            } else if Delimiter::is_separator(delimiter, character) {
+                separator = Some(character);
+                break;
+            } else {
+                key.push(character);
+                if !character.is_whitespace() {
+                    key_length = key.len();
+                }
+            }
+        }
+        key.truncate(key_length);
 
-        // case empty key if delimiter cannot split or multiline part 2...
-        if key.is_empty() {
-            if self.content.contains_key(&self.last_key) {
-                self.add_multiline(line);
-                return;
+        Self::skip_whitespace(&mut characters);
+        // a whitespace separated key may still be followed by `=` or `:`
+        if separator.is_some_and(char::is_whitespace)
+            && characters
+                .next_if(|next| *next == '=' || *next == ':')
+                .is_some()
+        {
+            Self::skip_whitespace(&mut characters);
+        }
+        let mut value = String::new();
+        while let Some(character) = characters.next() {
+            match character {
+                '\\' => value.push_str(&Self::unescape(&mut characters, line_number)?),
+                _ => value.push(character),
             }
-            self.add(&line, value, line_number);
-            return;
         }
 
-        // check for multi line comment
-        if self.is_multiline(value) {
-            debug!("'{}' is a multiline", value);
-            self.last_key = self.sanitize_key(key);
+        if key.is_empty() {
+            trace!("Ignore empty key in line {}", line_number);
+            return Ok(());
         }
         self.add(key, value, line_number);
+        Ok(())
     }
 
-    fn add(&mut self, key: &str, value: &str, line_number: u32) -> Option<Line> {
-        // ignore whitespaces ent the end of key and at the beginning of value
-        let line = Line::new(key, value, line_number);
-        debug!("Adding to content {:?}", line);
-        return self.content.insert(line.key.clone(), line.clone());
+    fn skip_whitespace(characters: &mut Peekable<Chars>) {
+        while characters.next_if(|next| next.is_whitespace()).is_some() {}
     }
 
-    fn add_multiline(&mut self, line: &str) {
-        // get last line
-        let previous_line = self.content.get_mut(&self.last_key).unwrap();
-        previous_line.add_multiline(line);
-        self.last_key = String::from("");
+    /// Character after a `\`, `\uXXXX` escapes of surrogate pairs are combined
+    fn unescape(characters: &mut Peekable<Chars>, line_number: u32) -> Result<String, ProconError> {
+        let unescaped = match characters.next() {
+            // a backslash at the end of the file
+            None => return Ok(String::new()),
+            Some('t') => '\t',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('f') => '\x0c',
+            Some('u') => {
+                let code = Self::unicode_code(characters, line_number)?;
+                if !(0xD800..0xDC00).contains(&code) {
+                    return Ok(char::from_u32(code)
+                        .unwrap_or(char::REPLACEMENT_CHARACTER)
+                        .to_string());
+                }
+                // high surrogate, the low surrogate follows as its own escape
+                let mut lookahead = characters.clone();
+                if lookahead.next() == Some('\\') && lookahead.next() == Some('u') {
+                    let low = Self::unicode_code(&mut lookahead, line_number)?;
+                    if (0xDC00..0xE000).contains(&low) {
+                        *characters = lookahead;
+                        let combined = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                        return Ok(char::from_u32(combined)
+                            .unwrap_or(char::REPLACEMENT_CHARACTER)
+                            .to_string());
+                    }
+                }
+                char::REPLACEMENT_CHARACTER
+            }
+            // every other character stands for itself like `\=`, `\:`, `\ ` or `\\`
+            Some(character) => character,
+        };
+        Ok(unescaped.to_string())
     }
 
-    /// ignore whitespaces ent the end of key
-    fn sanitize_key(&self, key: &str) -> String {
-        key.trim_end().to_string()
+    fn unicode_code(
+        characters: &mut Peekable<Chars>,
+        line_number: u32,
+    ) -> Result<u32, ProconError> {
+        let digits: String = characters.take(4).collect();
+        match u32::from_str_radix(&digits, 16) {
+            Ok(code) if digits.len() == 4 => Ok(code),
+            _ => Err(ProconError {
+                message: [
+                    "Malformed \\uxxxx encoding in line ",
+                    &line_number.to_string(),
+                ]
+                .concat(),
+            }),
+        }
     }
 
-    /// ignore whitespaces ent the end of key
-    fn sanitize_value(&self, value: &str) -> String {
-        value.trim_start().to_string()
+    fn add(&mut self, key: String, value: String, line_number: u32) -> Option<Line> {
+        let line = Line {
+            key,
+            value,
+            line_number,
+        };
+        debug!("Adding to content {:?}", line);
+        if !self.pending_comments.is_empty() {
+            let comments = std::mem::take(&mut self.pending_comments);
+            self.comments.insert(line.key.clone(), comments);
+        }
+        self.content.insert(line.key.clone(), line.clone())
     }
 
-    fn consider_multiline(&self) -> bool {
-        !self.last_key.is_empty()
+    /// Comment without its `#` or `!` and the space after it
+    fn comment_text(line: &str) -> String {
+        let text = &line[1..];
+        text.strip_prefix(' ')
+            .unwrap_or(text)
+            .trim_end()
+            .to_string()
     }
 
     fn is_multiline(&self, value: &str) -> bool {
@@ -196,7 +414,7 @@ impl PropertyFileReader {
             if c != '\\' {
                 break;
             }
-            counter = counter + 1;
+            counter += 1;
         }
         let even_odd = counter % 2;
         debug!("{}", even_odd);
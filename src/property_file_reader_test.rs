@@ -1,11 +1,11 @@
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use linked_hash_map::LinkedHashMap;
 
     use crate::line::Line;
-    use crate::property_file_reader::{Delimiter, PropertyFileReader};
+    use crate::property_file_reader::{Delimiter, KeySegment, PropertyFileReader};
 
-    fn assert_content(map: &HashMap<String, Line>, key: &str, value: &str) {
+    fn assert_content(map: &LinkedHashMap<String, Line>, key: &str, value: &str) {
         assert_eq!(map.len(), 1);
         assert_eq!(map.get(key).unwrap().value, value);
     }
@@ -14,7 +14,9 @@ mod tests {
     fn process_line_delimiter_equals() {
         let line = "website=https://en.wikipedia.org/";
         let mut property_file = PropertyFileReader::new();
-        property_file.process_line(line, 1, &Delimiter::Equals);
+        property_file
+            .process_line(line, 1, Some(&Delimiter::Equals))
+            .unwrap();
 
         assert_content(
             &property_file.content,
@@ -27,7 +29,9 @@ mod tests {
     fn process_line_with_spaces_on_key() {
         let line = "website =https://en.wikipedia.org/";
         let mut property_file = PropertyFileReader::new();
-        property_file.process_line(line, 1, &Delimiter::Equals);
+        property_file
+            .process_line(line, 1, Some(&Delimiter::Equals))
+            .unwrap();
 
         assert_content(
             &property_file.content,
@@ -40,7 +44,9 @@ mod tests {
     fn process_line_with_spaces_on_value() {
         let line = "website= https://en.wikipedia.org/";
         let mut property_file = PropertyFileReader::new();
-        property_file.process_line(line, 1, &Delimiter::Equals);
+        property_file
+            .process_line(line, 1, Some(&Delimiter::Equals))
+            .unwrap();
 
         assert_content(
             &property_file.content,
@@ -53,7 +59,9 @@ mod tests {
     fn process_line_delimiter_colon() {
         let line = "website:https://en.wikipedia.org/";
         let mut property_file = PropertyFileReader::new();
-        property_file.process_line(line, 1, &Delimiter::Colon);
+        property_file
+            .process_line(line, 1, Some(&Delimiter::Colon))
+            .unwrap();
 
         assert_content(
             &property_file.content,
@@ -66,7 +74,9 @@ mod tests {
     fn process_line_delimiter_whitespace() {
         let line = "website https://en.wikipedia.org/";
         let mut property_file = PropertyFileReader::new();
-        property_file.process_line(line, 1, &Delimiter::Whitespace);
+        property_file
+            .process_line(line, 1, Some(&Delimiter::Whitespace))
+            .unwrap();
 
         assert_content(
             &property_file.content,
@@ -79,7 +89,9 @@ mod tests {
     fn process_line_empty_line() {
         let line = "";
         let mut property_file = PropertyFileReader::new();
-        property_file.process_line(line, 1, &Delimiter::Equals);
+        property_file
+            .process_line(line, 1, Some(&Delimiter::Equals))
+            .unwrap();
 
         assert!(&property_file.content.is_empty());
     }
@@ -88,7 +100,9 @@ mod tests {
     fn process_line_comment_hash() {
         let line = "#website=https://en.wikipedia.org/";
         let mut property_file = PropertyFileReader::new();
-        property_file.process_line(line, 1, &Delimiter::Equals);
+        property_file
+            .process_line(line, 1, Some(&Delimiter::Equals))
+            .unwrap();
 
         assert!(&property_file.content.is_empty());
     }
@@ -97,7 +111,9 @@ mod tests {
     fn process_line_comment_exclamation_mark() {
         let line = "!website=https://en.wikipedia.org/";
         let mut property_file = PropertyFileReader::new();
-        property_file.process_line(line, 1, &Delimiter::Whitespace);
+        property_file
+            .process_line(line, 1, Some(&Delimiter::Whitespace))
+            .unwrap();
 
         assert!(&property_file.content.is_empty());
     }
@@ -106,7 +122,9 @@ mod tests {
     fn process_line_empty_value() {
         let line = "empty";
         let mut property_file = PropertyFileReader::new();
-        property_file.process_line(line, 1, &Delimiter::Whitespace);
+        property_file
+            .process_line(line, 1, Some(&Delimiter::Whitespace))
+            .unwrap();
 
         assert_content(&property_file.content, "empty", "");
     }
@@ -117,8 +135,12 @@ mod tests {
         let line = "multiline=This line \\";
         let line2 = "#continues";
         let delimiter = Delimiter::Equals;
-        property_file.process_line(line, 1, &delimiter);
-        property_file.process_line(line2, 2, &delimiter);
+        property_file
+            .process_line(line, 1, Some(&delimiter))
+            .unwrap();
+        property_file
+            .process_line(line2, 2, Some(&delimiter))
+            .unwrap();
 
         assert_content(&property_file.content, "multiline", "This line #continues");
     }
@@ -129,8 +151,12 @@ mod tests {
         let line = "multiline=This line \\";
         let line2 = "continues";
         let delimiter = Delimiter::Equals;
-        property_file.process_line(line, 1, &delimiter);
-        property_file.process_line(line2, 2, &delimiter);
+        property_file
+            .process_line(line, 1, Some(&delimiter))
+            .unwrap();
+        property_file
+            .process_line(line2, 2, Some(&delimiter))
+            .unwrap();
 
         assert_content(&property_file.content, "multiline", "This line continues");
     }
@@ -141,8 +167,12 @@ mod tests {
         let line = "multiline=This line \\";
         let line2 = "    continues";
         let delimiter = Delimiter::Equals;
-        property_file.process_line(line, 1, &delimiter);
-        property_file.process_line(line2, 2, &delimiter);
+        property_file
+            .process_line(line, 1, Some(&delimiter))
+            .unwrap();
+        property_file
+            .process_line(line2, 2, Some(&delimiter))
+            .unwrap();
 
         assert_content(&property_file.content, "multiline", "This line continues");
     }
@@ -154,10 +184,14 @@ mod tests {
         let line = "evenKey = This is on one line\\\\";
         let line2 = "# This line is a normal comment and is not included in the value for evenKey";
         let delimiter = Delimiter::Equals;
-        property_file.process_line(line, 1, &delimiter);
-        property_file.process_line(line2, 2, &delimiter);
-
-        assert_content(&property_file.content, "evenKey", "This is on one line\\\\");
+        property_file
+            .process_line(line, 1, Some(&delimiter))
+            .unwrap();
+        property_file
+            .process_line(line2, 2, Some(&delimiter))
+            .unwrap();
+
+        assert_content(&property_file.content, "evenKey", "This is on one line\\");
     }
 
     #[test]
@@ -166,13 +200,17 @@ mod tests {
         let line = "oddKey = This is on one line\\\\\\";
         let line2 = "# This is line two off an odd key";
         let delimiter = Delimiter::Equals;
-        property_file.process_line(line, 1, &delimiter);
-        property_file.process_line(line2, 2, &delimiter);
+        property_file
+            .process_line(line, 1, Some(&delimiter))
+            .unwrap();
+        property_file
+            .process_line(line2, 2, Some(&delimiter))
+            .unwrap();
 
         assert_content(
             &property_file.content,
             "oddKey",
-            "This is on one line\\\\# This is line two off an odd key",
+            "This is on one line\\# This is line two off an odd key",
         );
     }
 
@@ -182,8 +220,12 @@ mod tests {
         let line = "welcome = Welcome to \\";
         let line2 = "          Wikipedia!";
         let delimiter = Delimiter::Equals;
-        property_file.process_line(line, 1, &delimiter);
-        property_file.process_line(line2, 2, &delimiter);
+        property_file
+            .process_line(line, 1, Some(&delimiter))
+            .unwrap();
+        property_file
+            .process_line(line2, 2, Some(&delimiter))
+            .unwrap();
 
         assert_content(&property_file.content, "welcome", "Welcome to Wikipedia!");
     }
@@ -193,7 +235,9 @@ mod tests {
         let mut property_file = PropertyFileReader::new();
         let line = "helloInJapanese = こんにちは";
         let delimiter = Delimiter::Equals;
-        property_file.process_line(line, 1, &delimiter);
+        property_file
+            .process_line(line, 1, Some(&delimiter))
+            .unwrap();
 
         assert_content(&property_file.content, "helloInJapanese", "こんにちは");
     }
@@ -201,47 +245,254 @@ mod tests {
     #[test]
     fn process_line_encoded() {
         let mut property_file = PropertyFileReader::new();
-        let line = "encodedHelloInJapanese = \\u3053\\u3093\\u306b\\u3061\\u306";
+        let line = "encodedHelloInJapanese = \\u3053\\u3093\\u306b\\u3061\\u306f";
         let delimiter = Delimiter::Equals;
-        property_file.process_line(line, 1, &delimiter);
+        property_file
+            .process_line(line, 1, Some(&delimiter))
+            .unwrap();
 
         assert_content(
             &property_file.content,
             "encodedHelloInJapanese",
-            "\\u3053\\u3093\\u306b\\u3061\\u306",
+            "こんにちは",
         );
     }
 
+    #[test]
+    fn process_line_encoded_surrogate_pair() {
+        let mut property_file = PropertyFileReader::new();
+        let line = "smiley=\\ud83d\\ude00";
+        property_file
+            .process_line(line, 1, Some(&Delimiter::Equals))
+            .unwrap();
+
+        assert_content(&property_file.content, "smiley", "😀");
+    }
+
+    #[test]
+    fn process_line_encoded_malformed() {
+        let mut property_file = PropertyFileReader::new();
+        let line = "broken=\\u306";
+        let error = property_file
+            .process_line(line, 3, Some(&Delimiter::Equals))
+            .unwrap_err();
+
+        assert_eq!("Malformed \\uxxxx encoding in line 3", error.message);
+    }
+
+    #[test]
+    fn process_line_escaped_delimiters_in_key() {
+        let mut property_file = PropertyFileReader::new();
+        let line = "key\\=with\\:escaped\\ chars = value";
+        property_file
+            .process_line(line, 1, Some(&Delimiter::Equals))
+            .unwrap();
+
+        assert_content(&property_file.content, "key=with:escaped chars", "value");
+    }
+
+    #[test]
+    fn process_line_escaped_characters_in_value() {
+        let mut property_file = PropertyFileReader::new();
+        let line = "value=\\ leading\\tand\\nnew line\\\\path\\x";
+        property_file
+            .process_line(line, 1, Some(&Delimiter::Equals))
+            .unwrap();
+
+        assert_content(
+            &property_file.content,
+            "value",
+            " leading\tand\nnew line\\pathx",
+        );
+    }
+
+    #[test]
+    fn process_line_leading_whitespace() {
+        let mut property_file = PropertyFileReader::new();
+        property_file
+            .process_line("  \t  # indented comment", 1, Some(&Delimiter::Equals))
+            .unwrap();
+        property_file
+            .process_line("    indented = value", 2, Some(&Delimiter::Equals))
+            .unwrap();
+
+        assert_content(&property_file.content, "indented", "value");
+        assert_eq!(
+            Some(&vec!["indented comment".to_string()]),
+            property_file.comments.get("indented")
+        );
+    }
+
+    #[test]
+    fn process_line_whitespace_delimiter_followed_by_equals() {
+        let mut property_file = PropertyFileReader::new();
+        let line = "key \t = value";
+        property_file
+            .process_line(line, 1, Some(&Delimiter::Whitespace))
+            .unwrap();
+
+        assert_content(&property_file.content, "key", "value");
+    }
+
+    #[test]
+    fn finish_continued_last_line() {
+        let mut property_file = PropertyFileReader::new();
+        let delimiter = Delimiter::Equals;
+        property_file
+            .process_line("last=value\\", 1, Some(&delimiter))
+            .unwrap();
+        assert!(property_file.content.is_empty());
+        property_file.finish(Some(&delimiter)).unwrap();
+
+        assert_content(&property_file.content, "last", "value");
+    }
+
     #[test]
     fn process_line_duplicate_key_last_wins() {
         let mut property_file = PropertyFileReader::new();
         let line = "duplicateKey = first";
         let line2 = "duplicateKey = second";
         let delimiter = Delimiter::Equals;
-        property_file.process_line(line, 1, &delimiter);
-        property_file.process_line(line2, 2, &delimiter);
+        property_file
+            .process_line(line, 1, Some(&delimiter))
+            .unwrap();
+        property_file
+            .process_line(line2, 2, Some(&delimiter))
+            .unwrap();
 
         assert_content(&property_file.content, "duplicateKey", "second");
     }
 
+    #[test]
+    fn process_line_keeps_file_order() {
+        let mut property_file = PropertyFileReader::new();
+        let delimiter = Delimiter::Equals;
+        property_file
+            .process_line("server.port=8080", 1, Some(&delimiter))
+            .unwrap();
+        property_file
+            .process_line("app.name=procon", 2, Some(&delimiter))
+            .unwrap();
+        property_file
+            .process_line("database.url=localhost", 3, Some(&delimiter))
+            .unwrap();
+
+        let keys: Vec<&String> = property_file.content.keys().collect();
+        assert_eq!(vec!["server.port", "app.name", "database.url"], keys);
+        let line_numbers: Vec<u32> = property_file
+            .content
+            .values()
+            .map(|line| line.line_number)
+            .collect();
+        assert_eq!(vec![1, 2, 3], line_numbers);
+    }
+
+    #[test]
+    fn key_segments_indexed() {
+        let segments = PropertyFileReader::key_segments("servers[0].hosts[1][2]");
+        assert_eq!(
+            "[Name(\"servers\"), Index(0), Name(\"hosts\"), Index(1), Index(2)]",
+            format!("{:?}", segments)
+        );
+    }
+
+    #[test]
+    fn key_segments_invalid_index_is_kept() {
+        let segments = PropertyFileReader::key_segments("servers[a].host[0");
+        match segments.as_slice() {
+            [KeySegment::Name(first), KeySegment::Name(second)] => {
+                assert_eq!("servers[a]", *first);
+                assert_eq!("host[0", *second);
+            }
+            _ => panic!("Wrong segments {:?}", segments),
+        }
+    }
+
     #[test]
     fn is_multiline_no_slash() {
         let property_file = PropertyFileReader::new();
         let line = "oddKey = This is on one line";
-        assert_eq!(false, property_file.is_multiline(&line));
+        assert!(!property_file.is_multiline(line));
     }
 
     #[test]
     fn is_multiline_odd_slash() {
         let property_file = PropertyFileReader::new();
         let line = "oddKey = This is on one line\\\\\\";
-        assert_eq!(true, property_file.is_multiline(&line));
+        assert!(property_file.is_multiline(line));
     }
 
     #[test]
     fn is_multiline_even_slash() {
         let property_file = PropertyFileReader::new();
         let line = "evenKey = This is on one line\\\\";
-        assert_eq!(false, property_file.is_multiline(&line));
+        assert!(!property_file.is_multiline(line));
+    }
+
+    #[test]
+    fn process_line_comments_of_next_key() {
+        let mut property_file = PropertyFileReader::new();
+        let delimiter = Delimiter::Equals;
+        property_file
+            .process_line("# database", 1, Some(&delimiter))
+            .unwrap();
+        property_file
+            .process_line("!  indented note", 2, Some(&delimiter))
+            .unwrap();
+        property_file
+            .process_line("db.url=localhost", 3, Some(&delimiter))
+            .unwrap();
+        property_file
+            .process_line("db.user=admin", 4, Some(&delimiter))
+            .unwrap();
+
+        assert_eq!(
+            Some(&vec!["database".to_string(), " indented note".to_string()]),
+            property_file.comments.get("db.url")
+        );
+        assert_eq!(None, property_file.comments.get("db.user"));
+    }
+
+    #[test]
+    fn process_line_detects_delimiter() {
+        let mut property_file = PropertyFileReader::new();
+        let lines = [
+            "equals=jdbc:postgresql://localhost:5432/user",
+            "colon: value",
+            "whitespace value",
+            "spaced = value",
+            "colon_after_whitespace : value",
+            "escaped\\=key=value",
+        ];
+        for (index, line) in lines.iter().enumerate() {
+            property_file
+                .process_line(line, index as u32 + 1, None)
+                .unwrap();
+        }
+
+        let content = &property_file.content;
+        assert_eq!(
+            "jdbc:postgresql://localhost:5432/user",
+            content.get("equals").unwrap().value
+        );
+        assert_eq!("value", content.get("colon").unwrap().value);
+        assert_eq!("value", content.get("whitespace").unwrap().value);
+        assert_eq!("value", content.get("spaced").unwrap().value);
+        assert_eq!(
+            "value",
+            content.get("colon_after_whitespace").unwrap().value
+        );
+        assert_eq!("value", content.get("escaped=key").unwrap().value);
+    }
+
+    #[test]
+    fn process_line_explicit_delimiter_overrides_detection() {
+        let line = "jdbc:url=postgresql";
+        let mut property_file = PropertyFileReader::new();
+        property_file
+            .process_line(line, 1, Some(&Delimiter::Equals))
+            .unwrap();
+
+        assert_content(&property_file.content, "jdbc:url", "postgresql");
     }
 }
@@ -0,0 +1,147 @@
+use std::str::FromStr;
+
+use log::debug;
+
+use crate::errors::ProconError;
+use crate::node::{Node, NodeType};
+use crate::nodes::Nodes;
+
+#[cfg(test)]
+#[path = "./query_test.rs"]
+mod query_test;
+
+#[derive(Debug, PartialEq)]
+enum QuerySegment {
+    Name(String),
+    /// `*` matches every child of an object or element of an array
+    AnyName,
+    Index(usize),
+    /// `[*]` matches every element of an array
+    AnyIndex,
+}
+
+/// Selection of subtrees like `spring.datasource.*` or `servers[*].host`
+#[derive(Debug)]
+pub struct Query {
+    segments: Vec<QuerySegment>,
+}
+
+impl FromStr for Query {
+    type Err = ProconError;
+
+    fn from_str(expression: &str) -> Result<Query, Self::Err> {
+        let invalid = || ProconError {
+            message: ["Invalid query: ", expression].concat(),
+        };
+
+        let mut segments: Vec<QuerySegment> = vec![];
+        for part in expression.split('.') {
+            let (name, mut rest) = match part.split_once('[') {
+                // unterminated selector like `servers[`
+                Some((_, "")) => return Err(invalid()),
+                Some((name, rest)) => (name, rest),
+                None => (part, ""),
+            };
+            match name {
+                "" if rest.is_empty() => return Err(invalid()),
+                "" => {}
+                "*" => segments.push(QuerySegment::AnyName),
+                _ => segments.push(QuerySegment::Name(name.to_string())),
+            }
+            while !rest.is_empty() {
+                let (index, remaining) = rest.split_once(']').ok_or_else(invalid)?;
+                match index {
+                    "*" => segments.push(QuerySegment::AnyIndex),
+                    _ => segments.push(QuerySegment::Index(
+                        index.parse::<usize>().map_err(|_| invalid())?,
+                    )),
+                }
+                rest = match (remaining, remaining.strip_prefix('[')) {
+                    ("", _) => "",
+                    (_, Some(next)) if !next.is_empty() => next,
+                    _ => return Err(invalid()),
+                };
+            }
+        }
+        debug!("Query segments: {:?}", segments);
+        Ok(Query { segments })
+    }
+}
+
+impl Query {
+    /// Only the parts of the nodes matching the query, the paths to them are kept
+    pub fn select(&self, nodes: &Nodes) -> Nodes {
+        let mut selected = Nodes::new();
+        let Some(first) = self.segments.first() else {
+            return selected;
+        };
+        for node in nodes.iter() {
+            let matching_root = match first {
+                QuerySegment::Name(name) => &node.name == name,
+                QuerySegment::AnyName => true,
+                // indices address a root list
+                QuerySegment::Index(_) | QuerySegment::AnyIndex => node.name.is_empty(),
+            };
+            if !matching_root {
+                continue;
+            }
+            let rest = match first {
+                QuerySegment::Name(_) | QuerySegment::AnyName => &self.segments[1..],
+                QuerySegment::Index(_) | QuerySegment::AnyIndex => &self.segments[..],
+            };
+            if let Some(selected_node) = select_node(node, rest) {
+                selected.push(selected_node);
+            }
+        }
+        selected
+    }
+}
+
+fn select_node(node: &Node, segments: &[QuerySegment]) -> Option<Node> {
+    let Some(segment) = segments.first() else {
+        return Some(node.clone());
+    };
+
+    let mut selected_node = node.clone();
+    match (&node.value, segment) {
+        (NodeType::ARRAY(elements), _) => {
+            let mut selected_elements: Vec<Node> = vec![];
+            for (index, element) in elements.iter().enumerate() {
+                let matching = match segment {
+                    QuerySegment::Index(query_index) => index == *query_index,
+                    QuerySegment::AnyIndex | QuerySegment::AnyName => true,
+                    QuerySegment::Name(_) => false,
+                };
+                if let Some(mut selected_element) = matching
+                    .then(|| select_node(element, &segments[1..]))
+                    .flatten()
+                {
+                    // selected elements form a new array
+                    selected_element.name = selected_elements.len().to_string();
+                    selected_elements.push(selected_element);
+                }
+            }
+            if selected_elements.is_empty() {
+                return None;
+            }
+            selected_node.value = NodeType::ARRAY(selected_elements);
+        }
+        (NodeType::NONE, QuerySegment::Name(_) | QuerySegment::AnyName) => {
+            selected_node.children = node
+                .children
+                .iter()
+                .filter(|child| match segment {
+                    QuerySegment::Name(name) => &child.name == name,
+                    _ => true,
+                })
+                .filter_map(|child| select_node(child, &segments[1..]))
+                .collect();
+            if selected_node.children.is_empty() {
+                return None;
+            }
+        }
+        // scalars have no nested nodes to select
+        _ => return None,
+    }
+    Some(selected_node)
+}
@@ -0,0 +1,94 @@
+#[cfg(test)]
+mod tests {
+    use crate::node::Node;
+    use crate::nodes::Nodes;
+    use crate::property_file_reader::PropertyFileReader;
+    use crate::query::{Query, QuerySegment};
+
+    fn nodes(properties: &[(&str, &str)]) -> Nodes {
+        let mut nodes = Nodes::new();
+        for (key, value) in properties {
+            let mut node: Node = PropertyFileReader::property_to_node(key, value).unwrap();
+            nodes.merge(&mut node);
+        }
+        nodes
+    }
+
+    #[test]
+    fn parse_query() {
+        let query: Query = "servers[*].host".parse().unwrap();
+        assert_eq!(
+            vec![
+                QuerySegment::Name(String::from("servers")),
+                QuerySegment::AnyIndex,
+                QuerySegment::Name(String::from("host")),
+            ],
+            query.segments
+        );
+
+        let query: Query = "spring.*[1][0]".parse().unwrap();
+        assert_eq!(
+            vec![
+                QuerySegment::Name(String::from("spring")),
+                QuerySegment::AnyName,
+                QuerySegment::Index(1),
+                QuerySegment::Index(0),
+            ],
+            query.segments
+        );
+    }
+
+    #[test]
+    fn parse_invalid_query() {
+        assert!("spring..name".parse::<Query>().is_err());
+        assert!("servers[x]".parse::<Query>().is_err());
+        assert!("servers[0".parse::<Query>().is_err());
+        assert!("servers[0]x".parse::<Query>().is_err());
+        assert!("a[".parse::<Query>().is_err());
+        assert!("a[0][".parse::<Query>().is_err());
+        assert!("a[.b".parse::<Query>().is_err());
+    }
+
+    #[test]
+    fn select_wildcard_children() {
+        let nodes = nodes(&[
+            ("spring.datasource.url", "jdbc:h2:mem"),
+            ("spring.datasource.username", "sa"),
+            ("spring.application.name", "demo"),
+            ("server.port", "8080"),
+        ]);
+
+        let query: Query = "spring.datasource.*".parse().unwrap();
+        let selected = query.select(&nodes);
+        assert_eq!(
+            "spring.datasource.url=jdbc:h2:mem\nspring.datasource.username=sa\n",
+            selected.get(0).unwrap().to_properties(false)
+        );
+        assert!(selected.get(1).is_none());
+    }
+
+    #[test]
+    fn select_array_elements() {
+        let nodes = nodes(&[
+            ("servers[0].host", "alpha"),
+            ("servers[0].port", "8001"),
+            ("servers[1].host", "beta"),
+            ("servers[1].port", "8002"),
+        ]);
+
+        let query: Query = "servers[*].host".parse().unwrap();
+        assert_eq!(
+            "servers[0].host=alpha\nservers[1].host=beta\n",
+            query.select(&nodes).get(0).unwrap().to_properties(false)
+        );
+
+        let query: Query = "servers[1]".parse().unwrap();
+        assert_eq!(
+            "servers[0].host=beta\nservers[0].port=8002\n",
+            query.select(&nodes).get(0).unwrap().to_properties(false)
+        );
+
+        let query: Query = "servers[5]".parse().unwrap();
+        assert!(query.select(&nodes).is_empty());
+    }
+}
@@ -0,0 +1,101 @@
+use log::info;
+use toml::Value;
+
+use crate::args::Args;
+use crate::errors::ProconError;
+use crate::node::{Node, NodeType};
+use crate::nodes::Nodes;
+
+#[cfg(test)]
+#[path = "./toml_file_reader_test.rs"]
+mod toml_file_reader_test;
+
+pub struct TomlFileReader {}
+
+impl TomlFileReader {
+    pub fn parse(_args: &Args, content: &str) -> Result<Nodes, ProconError> {
+        info!("Use TomlFileReader");
+        let toml_value: Value = toml::from_str(content).map_err(|_| ProconError {
+            message: "Wrong toml format".to_string(),
+        })?;
+
+        Self::convert_toml_values_to_nodes(&toml_value)
+    }
+
+    pub(crate) fn convert_toml_values_to_nodes(toml_value: &Value) -> Result<Nodes, ProconError> {
+        let mut nodes: Nodes = Nodes::new();
+        match toml_value {
+            Value::Table(table) => {
+                for (key, value) in table.iter() {
+                    let mut parent = Self::toml_to_node(key, value, None, 0);
+                    nodes.merge(&mut parent);
+                }
+            }
+            // toml documents are always a table at root level
+            _ => {
+                return Err(ProconError {
+                    message: "Not valid toml".to_string(),
+                })
+            }
+        }
+        Ok(nodes)
+    }
+
+    fn toml_to_node(key: &str, value: &Value, parent: Option<&mut Node>, level: usize) -> Node {
+        let mut new_node: Node = if level == 0 {
+            Node::new_from_name(key)
+        } else {
+            Node::new_child(level, parent.unwrap(), key)
+        };
+
+        match value {
+            Value::Table(table) => {
+                new_node.children = Self::table_to_children(table, &mut new_node, level);
+            }
+            Value::Array(array) => {
+                let mut elements: Vec<Node> = vec![];
+                for (index, element) in array.iter().enumerate() {
+                    let element_node = Self::toml_to_node(
+                        &index.to_string(),
+                        element,
+                        Some(&mut new_node),
+                        level + 1,
+                    );
+                    elements.push(element_node);
+                }
+                new_node.value = NodeType::ARRAY(elements);
+            }
+            // toml values are typed, no need to guess the type
+            Value::String(string_value) => new_node.value = NodeType::STRING(string_value.clone()),
+            Value::Integer(integer_value) => {
+                new_node.value = NodeType::NUMERIC(integer_value.to_string())
+            }
+            Value::Float(float_value) => {
+                new_node.value = NodeType::NUMERIC(float_text(*float_value))
+            }
+            Value::Boolean(bool_value) => new_node.value = NodeType::BOOLEAN(*bool_value),
+            Value::Datetime(datetime_value) => {
+                new_node.value = NodeType::STRING(datetime_value.to_string())
+            }
+        }
+        new_node
+    }
+
+    fn table_to_children(table: &toml::value::Table, parent: &mut Node, level: usize) -> Vec<Node> {
+        let mut children: Vec<Node> = vec![];
+        for (key, value) in table.iter() {
+            children.push(Self::toml_to_node(key, value, Some(parent), level + 1));
+        }
+        children
+    }
+}
+
+/// Floats keep their fraction (`3.0`) and the toml spelling of nan and infinity
+fn float_text(value: f64) -> String {
+    match value {
+        _ if value.is_nan() => String::from("nan"),
+        _ if value.is_infinite() && value > 0.0 => String::from("inf"),
+        _ if value.is_infinite() => String::from("-inf"),
+        _ => format!("{:?}", value),
+    }
+}
@@ -0,0 +1,132 @@
+#[cfg(test)]
+mod tests {
+    use toml::Value;
+
+    use crate::node::NodeType;
+    use crate::toml_file_reader::TomlFileReader;
+
+    #[test]
+    fn convert_toml_values_to_nodes_multiple_nodes() {
+        let content = String::from(
+            "[writer.datasource]
+jdbc-url = \"localhost\"
+
+[reader.datasource]
+jdbc-url = \"localhost\"",
+        );
+        let toml_data: Value = toml::from_str(&content).expect("Unable to parse");
+        let nodes = TomlFileReader::convert_toml_values_to_nodes(&toml_data).unwrap();
+
+        let writer_node = nodes.get(0).unwrap();
+        assert_eq!("writer", writer_node.name);
+        let datasource_node = writer_node.children.first().unwrap();
+        assert_eq!("datasource", datasource_node.name);
+        let jdbc_url_node = datasource_node.children.first().unwrap();
+        assert_eq!("jdbc-url", jdbc_url_node.name);
+        assert_eq!("localhost", jdbc_url_node.value.to_string());
+
+        let reader_node = nodes.get(1).unwrap();
+        assert_eq!("reader", reader_node.name);
+        let datasource_node = reader_node.children.first().unwrap();
+        assert_eq!("datasource", datasource_node.name);
+        let jdbc_url_node = datasource_node.children.first().unwrap();
+        assert_eq!("jdbc-url", jdbc_url_node.name);
+        assert_eq!("localhost", jdbc_url_node.value.to_string());
+    }
+
+    #[test]
+    fn convert_toml_values_to_nodes_array() {
+        let content = String::from("readers = [\"value-1\", \"value-2\"]");
+        let toml_data: Value = toml::from_str(&content).expect("Unable to parse");
+        let nodes = TomlFileReader::convert_toml_values_to_nodes(&toml_data).unwrap();
+
+        let reader_node = nodes.get(0).unwrap();
+        assert_eq!("readers", reader_node.name);
+        assert_eq!("value-1,value-2", reader_node.value.to_string());
+    }
+
+    #[test]
+    fn convert_toml_values_to_nodes_array_of_tables() {
+        let content = String::from(
+            "[[servers]]
+host = \"a\"
+
+[[servers]]
+host = \"b\"",
+        );
+        let toml_data: Value = toml::from_str(&content).expect("Unable to parse");
+        let nodes = TomlFileReader::convert_toml_values_to_nodes(&toml_data).unwrap();
+
+        let servers_node = nodes.get(0).unwrap();
+        assert_eq!("servers", servers_node.name);
+        let elements = match &servers_node.value {
+            NodeType::ARRAY(elements) => elements,
+            _ => panic!("Wrong type"),
+        };
+        assert_eq!(2, elements.len());
+
+        let first_node = elements.first().unwrap();
+        assert_eq!("a", first_node.children[0].value.to_string());
+        let second_node = elements.get(1).unwrap();
+        assert_eq!("b", second_node.children[0].value.to_string());
+    }
+
+    #[test]
+    fn convert_toml_values_to_nodes_bool() {
+        let content = String::from("isReader = true");
+        let toml_data: Value = toml::from_str(&content).expect("Unable to parse");
+        let nodes = TomlFileReader::convert_toml_values_to_nodes(&toml_data).unwrap();
+
+        let reader_node = nodes.get(0).unwrap();
+        assert_eq!("isReader", reader_node.name);
+        assert_eq!("true", reader_node.value.to_string());
+    }
+
+    #[test]
+    fn convert_toml_values_to_nodes_number() {
+        let content = String::from("isReader = 1");
+        let toml_data: Value = toml::from_str(&content).expect("Unable to parse");
+        let nodes = TomlFileReader::convert_toml_values_to_nodes(&toml_data).unwrap();
+
+        let reader_node = nodes.get(0).unwrap();
+        assert_eq!("isReader", reader_node.name);
+        assert_eq!("1", reader_node.value.to_string());
+    }
+
+    #[test]
+    fn convert_toml_values_to_nodes_float_number() {
+        let content = String::from("isReader = 1.78");
+        let toml_data: Value = toml::from_str(&content).expect("Unable to parse");
+        let nodes = TomlFileReader::convert_toml_values_to_nodes(&toml_data).unwrap();
+
+        let reader_node = nodes.get(0).unwrap();
+        assert_eq!("isReader", reader_node.name);
+        assert_eq!("1.78", reader_node.value.to_string());
+    }
+
+    #[test]
+    fn convert_toml_values_to_nodes_keep_floats() {
+        let content = String::from("ratio = 3.0\nexp = 1e5\nlimit = inf\nmissing = nan");
+        let toml_data: Value = toml::from_str(&content).expect("Unable to parse");
+        let nodes = TomlFileReader::convert_toml_values_to_nodes(&toml_data).unwrap();
+
+        let values: Vec<NodeType> = nodes.iter().map(|node| node.value.clone()).collect();
+        assert_eq!(
+            vec![
+                NodeType::NUMERIC("3.0".into()),
+                NodeType::NUMERIC("100000.0".into()),
+                NodeType::NUMERIC("inf".into()),
+                NodeType::NUMERIC("nan".into()),
+            ],
+            values
+        );
+    }
+
+    #[test]
+    fn convert_toml_values_to_nodes_empty_toml() {
+        let toml_data: Value = toml::from_str("").expect("Unable to parse");
+        let nodes = TomlFileReader::convert_toml_values_to_nodes(&toml_data).unwrap();
+
+        assert_eq!(None, nodes.get(0));
+    }
+}
@@ -1,9 +1,15 @@
-use log::info;
-use serde_yaml::Value;
+use std::collections::HashMap;
+
+use linked_hash_map::LinkedHashMap;
+use log::{debug, info};
+use serde::Deserialize;
+use serde_yaml::{Mapping, Value};
+use yaml_rust::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust::scanner::{Marker, TScalarStyle, TokenType};
 
 use crate::args::Args;
 use crate::errors::ProconError;
-use crate::node::{Node, NodeType};
+use crate::node::{is_decimal_number, is_same_number, Node, NodeType};
 use crate::nodes::Nodes;
 
 #[cfg(test)]
@@ -11,33 +17,532 @@ use crate::nodes::Nodes;
 mod yaml_file_reader_test;
 
 pub struct YamlFileReader {}
+
+/// Position inside a yaml collection while walking the parser events
+enum Collection {
+    Mapping { key: Option<String> },
+    Sequence { index: usize },
+}
+
+/// Collects the line of every key and sequence element and the source details of the scalars
+/// by their dotted key
+#[derive(Default)]
+struct LineNumberReceiver {
+    collections: Vec<(String, Collection)>,
+    line_numbers: LinkedHashMap<String, u32>,
+    /// source details of the scalar values of every document
+    documents: Vec<DocumentScalars>,
+    /// anchor id, key and depth of the open anchored collections
+    anchored_collections: Vec<(usize, String, usize)>,
+    /// source details of anchored values by anchor id, keys are relative to the anchored value
+    anchors: HashMap<usize, DocumentScalars>,
+}
+
+/// Source details of the scalar values of a yaml document by their dotted key, which the
+/// parser does not keep
+#[derive(Default)]
+struct DocumentScalars {
+    /// source text of plain scalars like `1.10`
+    plain: LinkedHashMap<String, String>,
+    /// core tags the parser drops like `!!binary`
+    core_tags: LinkedHashMap<String, String>,
+}
+
+impl DocumentScalars {
+    /// Details of the value of the key and its nested values, keys relative to the key
+    fn below(&self, key: &str) -> DocumentScalars {
+        let relative = |entries: &LinkedHashMap<String, String>| {
+            entries
+                .iter()
+                .filter_map(|(entry_key, value)| {
+                    let rest = entry_key.strip_prefix(key)?;
+                    let nested = key.is_empty() || rest.is_empty() || rest.starts_with(['.', '[']);
+                    nested.then(|| (rest.to_string(), value.clone()))
+                })
+                .collect()
+        };
+        DocumentScalars {
+            plain: relative(&self.plain),
+            core_tags: relative(&self.core_tags),
+        }
+    }
+
+    /// Adds the details of an anchored value at the key, merged values keep existing entries
+    fn insert_at(&mut self, key: &str, anchored: &DocumentScalars, merge: bool) {
+        let targets = [
+            (&mut self.plain, &anchored.plain),
+            (&mut self.core_tags, &anchored.core_tags),
+        ];
+        for (entries, anchored_entries) in targets {
+            for (relative, value) in anchored_entries {
+                let entry_key = match (key.is_empty(), relative.starts_with(['.', '['])) {
+                    (true, _) => relative.trim_start_matches('.').to_string(),
+                    (false, true) => [key, relative].concat(),
+                    (false, false) if relative.is_empty() => key.to_string(),
+                    (false, false) => [key, ".", relative].concat(),
+                };
+                if !merge || !entries.contains_key(&entry_key) {
+                    entries.insert(entry_key, value.clone());
+                }
+            }
+        }
+    }
+}
+
+/// Core tags resolved by the parser into the type of the value
+const RESOLVED_CORE_TAGS: [&str; 5] = ["str", "int", "float", "bool", "null"];
+
+impl LineNumberReceiver {
+    fn current_key(&self) -> Option<String> {
+        let (key, collection) = self.collections.last()?;
+        match collection {
+            Collection::Mapping { key: Some(name) } if key.is_empty() => Some(name.clone()),
+            Collection::Mapping { key: Some(name) } => Some([key, ".", name].concat()),
+            Collection::Mapping { key: None } => None,
+            Collection::Sequence { index } => Some([key, "[", &index.to_string(), "]"].concat()),
+        }
+    }
+
+    /// Explicitly typed scalars like `!!float 123` are converted by their tag
+    fn scalar_value(
+        &mut self,
+        value: &str,
+        style: TScalarStyle,
+        tag: Option<TokenType>,
+        anchor: usize,
+    ) {
+        let (Some(key), Some(document)) = (self.current_key(), self.documents.last_mut()) else {
+            return;
+        };
+        match tag {
+            None if style == TScalarStyle::Plain => {
+                document.plain.insert(key.clone(), value.to_string());
+            }
+            Some(TokenType::Tag(handle, suffix))
+                if handle == "!!" && !RESOLVED_CORE_TAGS.contains(&suffix.as_str()) =>
+            {
+                document
+                    .core_tags
+                    .insert(key.clone(), [handle, suffix].concat());
+            }
+            _ => {}
+        }
+        if anchor > 0 {
+            self.anchor_value(anchor, &key);
+        }
+    }
+
+    /// A value of the current collection is complete
+    fn value_done(&mut self) {
+        match self.collections.last_mut() {
+            Some((_, Collection::Mapping { key })) => *key = None,
+            Some((_, Collection::Sequence { index })) => *index += 1,
+            None => {}
+        }
+    }
+
+    fn start_collection(&mut self, collection: Collection, line: u32, anchor: usize) {
+        let key = self.current_key().unwrap_or_default();
+        if matches!(
+            self.collections.last(),
+            Some((_, Collection::Sequence { .. }))
+        ) {
+            self.line_numbers.insert(key.clone(), line);
+        }
+        if anchor > 0 {
+            let depth = self.collections.len() + 1;
+            self.anchored_collections.push((anchor, key.clone(), depth));
+        }
+        self.collections.push((key, collection));
+    }
+
+    fn end_collection(&mut self) {
+        if let Some((anchor, key, _)) = self
+            .anchored_collections
+            .pop_if(|(_, _, depth)| *depth == self.collections.len())
+        {
+            self.anchor_value(anchor, &key);
+        }
+        self.collections.pop();
+        self.value_done();
+    }
+
+    /// Remembers the details of an anchored value for its aliases
+    fn anchor_value(&mut self, anchor: usize, key: &str) {
+        if let Some(document) = self.documents.last() {
+            self.anchors.insert(anchor, document.below(key));
+        }
+    }
+
+    /// Aliases and merge keys like `<<: *defaults` get the details of the anchored value
+    fn alias_value(&mut self, anchor: usize) {
+        let (Some(key), Some(anchored)) = (self.current_key(), self.anchors.get(&anchor)) else {
+            return;
+        };
+        // merged mappings are aliases below `<<` or a sequence of aliases below `<<`
+        let merge_key = match key.rsplit_once('[') {
+            Some((name, _)) if name == "<<" || name.ends_with(".<<") => name,
+            _ => key.as_str(),
+        };
+        let merged = match merge_key.strip_suffix("<<") {
+            Some(target) if target.is_empty() || target.ends_with('.') => {
+                Some(target.trim_end_matches('.'))
+            }
+            _ => None,
+        };
+        if let Some(document) = self.documents.last_mut() {
+            match merged {
+                Some(target) => document.insert_at(target, anchored, true),
+                None => document.insert_at(&key, anchored, false),
+            }
+        }
+    }
+}
+
+impl MarkedEventReceiver for LineNumberReceiver {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        let line = mark.line() as u32;
+        match event {
+            Event::MappingStart(anchor) => {
+                self.start_collection(Collection::Mapping { key: None }, line, anchor)
+            }
+            Event::SequenceStart(anchor) => {
+                self.start_collection(Collection::Sequence { index: 0 }, line, anchor)
+            }
+            Event::MappingEnd | Event::SequenceEnd => self.end_collection(),
+            Event::DocumentStart => self.documents.push(DocumentScalars::default()),
+            Event::Scalar(value, style, anchor, tag) => match self.collections.last_mut() {
+                // a key of a mapping
+                Some((_, Collection::Mapping { key: key @ None })) => {
+                    *key = Some(value);
+                    let current_key = self.current_key().unwrap_or_default();
+                    self.line_numbers.insert(current_key, line);
+                }
+                Some((_, Collection::Sequence { .. })) => {
+                    let current_key = self.current_key().unwrap_or_default();
+                    self.line_numbers.insert(current_key, line);
+                    self.scalar_value(&value, style, tag, anchor);
+                    self.value_done();
+                }
+                _ => {
+                    self.scalar_value(&value, style, tag, anchor);
+                    self.value_done()
+                }
+            },
+            Event::Alias(anchor) => {
+                self.alias_value(anchor);
+                self.value_done()
+            }
+            _ => {}
+        }
+    }
+}
+
 impl YamlFileReader {
-    pub fn parse(_args: &Args, content: &String) -> Result<Nodes, ProconError> {
+    /// Line numbers of all keys and sequence elements by their dotted key like `servers[0].host`
+    pub fn line_numbers(content: &str) -> LinkedHashMap<String, u32> {
+        let mut receiver = LineNumberReceiver::default();
+        let mut parser = Parser::new(content.chars());
+        if parser.load(&mut receiver, false).is_err() {
+            info!("Could not read line numbers");
+        }
+        receiver.line_numbers
+    }
+
+    /// Source details of the scalar values of every document
+    fn document_scalars(content: &str) -> Vec<DocumentScalars> {
+        let mut receiver = LineNumberReceiver::default();
+        let mut parser = Parser::new(content.chars());
+        if parser.load(&mut receiver, true).is_err() {
+            info!("Could not read the source details of the scalars");
+        }
+        receiver.documents
+    }
+
+    pub fn parse(args: &Args, content: &str) -> Result<Nodes, ProconError> {
         info!("Use YamlFileReader");
-        let yaml_value: Value = serde_yaml::from_str(&content).map_err(|_| ProconError {
-            message: "Wrong yaml format".to_string(),
-        })?;
+        let documents = Self::parse_documents(content)?;
+        let mut nodes = Self::select_documents(args, documents)?;
+        Self::attach_comments(&mut nodes, content);
+        Ok(nodes)
+    }
 
-        Self::convert_yaml_values_to_nodes(&yaml_value)
+    /// Comment lines above a key belong to the first key of the next line, comments at the end
+    /// of a line to its last key
+    pub(crate) fn attach_comments(nodes: &mut Nodes, content: &str) {
+        let lines: Vec<&str> = content.lines().collect();
+        let comment_lines = Self::comment_lines(&lines);
+        let mut keys_by_line: LinkedHashMap<u32, Vec<String>> = LinkedHashMap::new();
+        for (key, line_number) in Self::line_numbers(content) {
+            keys_by_line.entry(line_number).or_default().push(key);
+        }
+
+        for (line_number, keys) in keys_by_line {
+            let index = (line_number as usize).saturating_sub(1);
+            let mut comments: Vec<String> = vec![];
+            for previous in (0..index).rev() {
+                match &comment_lines[previous] {
+                    Some(comment) => comments.insert(0, comment.clone()),
+                    None if lines[previous].trim().is_empty() => {}
+                    None => break,
+                }
+            }
+            if let Some(node) = nodes.find_mut(&keys[0]) {
+                node.comments = comments;
+            }
+            let trailing_comment = lines
+                .get(index)
+                .and_then(|line| Self::trailing_comment(line));
+            if let Some(node) = nodes.find_mut(keys.last().unwrap()) {
+                node.trailing_comment = trailing_comment;
+            }
+        }
+        // comment lines below the last value are not followed by a key
+        let mut trailing_comments = vec![];
+        for (index, line) in lines.iter().enumerate().rev() {
+            match &comment_lines[index] {
+                Some(comment) => trailing_comments.insert(0, comment.clone()),
+                None if line.trim().is_empty() => {}
+                None => break,
+            }
+        }
+        nodes.trailing_comments = trailing_comments;
+    }
+
+    /// Text of every comment line, lines of block scalars like `|` are never comments
+    fn comment_lines(lines: &[&str]) -> Vec<Option<String>> {
+        let mut comment_lines = vec![];
+        let mut block_indent: Option<usize> = None;
+        for line in lines {
+            let indent = line.len() - line.trim_start().len();
+            let trimmed = line.trim();
+            if let Some(block) = block_indent {
+                if trimmed.is_empty() || indent > block {
+                    comment_lines.push(None);
+                    continue;
+                }
+                block_indent = None;
+            }
+            match trimmed.strip_prefix('#') {
+                Some(text) => comment_lines.push(Some(
+                    text.strip_prefix(' ')
+                        .unwrap_or(text)
+                        .trim_end()
+                        .to_string(),
+                )),
+                None => comment_lines.push(None),
+            }
+            let value = match Self::trailing_comment_start(line) {
+                Some(start) => line[..start].trim_end(),
+                None => trimmed,
+            };
+            let indicator = value.split_whitespace().last().unwrap_or_default();
+            if indicator.starts_with(['|', '>'])
+                && indicator[1..].chars().all(|character| {
+                    character == '-' || character == '+' || character.is_ascii_digit()
+                })
+            {
+                block_indent = Some(indent);
+            }
+        }
+        comment_lines
+    }
+
+    fn trailing_comment(line: &str) -> Option<String> {
+        let start = Self::trailing_comment_start(line)?;
+        let text = &line[start + 1..];
+        Some(
+            text.strip_prefix(' ')
+                .unwrap_or(text)
+                .trim_end()
+                .to_string(),
+        )
+    }
+
+    /// Position of a `#` after a value, which is not part of a quoted string
+    fn trailing_comment_start(line: &str) -> Option<usize> {
+        let mut quote: Option<char> = None;
+        let mut previous = ' ';
+        for (index, character) in line.char_indices() {
+            match (quote, character) {
+                (Some(open), _) if character == open => quote = None,
+                (Some(_), _) => {}
+                (None, '"' | '\'') if previous.is_whitespace() || "[{,".contains(previous) => {
+                    quote = Some(character)
+                }
+                (None, '#') if previous.is_whitespace() && !line[..index].trim().is_empty() => {
+                    return Some(index)
+                }
+                _ => {}
+            }
+            previous = character;
+        }
+        None
+    }
+
+    /// Nodes of every document of a yaml stream separated by `---`, empty documents are left out
+    pub fn parse_documents(content: &str) -> Result<Vec<Nodes>, ProconError> {
+        let mut documents: Vec<Nodes> = vec![];
+        let document_scalars = Self::document_scalars(content);
+        for (index, document) in serde_yaml::Deserializer::from_str(content).enumerate() {
+            // aliases are already expanded by the parser
+            let mut yaml_value = Value::deserialize(document).map_err(|_| ProconError {
+                message: "Wrong yaml format".to_string(),
+            })?;
+            Self::apply_merge_keys(&mut yaml_value)?;
+            if yaml_value.is_null() {
+                continue;
+            }
+            let mut nodes = Self::convert_yaml_values_to_nodes(&yaml_value)?;
+            if let Some(scalars) = document_scalars.get(index) {
+                Self::keep_number_lexemes(&mut nodes, &scalars.plain);
+                Self::keep_core_tags(&mut nodes, &scalars.core_tags);
+            }
+            documents.push(nodes);
+        }
+        debug!("Read {} yaml documents", documents.len());
+        Ok(documents)
+    }
+
+    /// The parser turns numbers like `1.10` into `1.1`, decimal numbers keep their source text
+    fn keep_number_lexemes(nodes: &mut Nodes, plain_scalars: &LinkedHashMap<String, String>) {
+        for (key, lexeme) in plain_scalars {
+            let Some(node) = nodes.find_mut(key) else {
+                continue;
+            };
+            if let NodeType::NUMERIC(value) = &node.value {
+                if is_decimal_number(lexeme) && is_same_number(value, lexeme) {
+                    node.value = NodeType::NUMERIC(lexeme.clone());
+                }
+            }
+        }
     }
-    fn convert_yaml_values_to_nodes(yaml_value: &Value) -> Result<Nodes, ProconError> {
+
+    /// The parser drops core tags like `!!binary` and keeps the plain value
+    fn keep_core_tags(nodes: &mut Nodes, core_tags: &LinkedHashMap<String, String>) {
+        for (key, tag) in core_tags {
+            if let Some(node) = nodes.find_mut(key) {
+                node.tag = Some(tag.clone());
+            }
+        }
+    }
+
+    /// Replaces `<<` merge keys by the entries of the merged mappings at the position of the
+    /// merge key, explicit keys win over merged ones and earlier merged mappings over later ones
+    fn apply_merge_keys(value: &mut Value) -> Result<(), ProconError> {
+        match value {
+            Value::Mapping(mapping) if mapping.contains_key("<<") => {
+                let invalid_merge = || ProconError {
+                    message: "Only mappings can be merged with <<".to_string(),
+                };
+                let mut merged_mapping = Mapping::new();
+                for (key, entry) in std::mem::take(mapping) {
+                    if key.as_str() != Some("<<") {
+                        // explicit keys replace merged ones and keep their position
+                        merged_mapping.insert(key, entry);
+                        continue;
+                    }
+                    let sources = match entry {
+                        Value::Sequence(sources) => sources,
+                        source => vec![source],
+                    };
+                    for mut source in sources {
+                        // merged mappings can contain merge keys themselves
+                        Self::apply_merge_keys(&mut source)?;
+                        let Value::Mapping(source) = source else {
+                            return Err(invalid_merge());
+                        };
+                        for (merged_key, merged_entry) in source {
+                            if !merged_mapping.contains_key(&merged_key) {
+                                merged_mapping.insert(merged_key, merged_entry);
+                            }
+                        }
+                    }
+                }
+                *mapping = merged_mapping;
+                for entry in mapping.values_mut() {
+                    Self::apply_merge_keys(entry)?;
+                }
+            }
+            Value::Mapping(mapping) => {
+                for entry in mapping.values_mut() {
+                    Self::apply_merge_keys(entry)?;
+                }
+            }
+            Value::Sequence(sequence) => {
+                for entry in sequence {
+                    Self::apply_merge_keys(entry)?;
+                }
+            }
+            Value::Tagged(tagged) => Self::apply_merge_keys(&mut tagged.value)?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// The document of the given index or all documents active for the given Spring profile
+    /// merged in order, later documents override earlier ones
+    ///
+    /// Without a profile only the documents without profile activation of a multi-document file
+    /// are active
+    fn select_documents(args: &Args, documents: Vec<Nodes>) -> Result<Nodes, ProconError> {
+        if let Some(index) = args.document {
+            return documents.into_iter().nth(index).ok_or(ProconError {
+                message: ["No yaml document with index ", &index.to_string()].concat(),
+            });
+        }
+
+        let multi_document = documents.len() > 1;
+        let mut active_documents = documents.into_iter().filter(|document| {
+            match (&args.profile, Self::document_profiles(document)) {
+                (Some(profile), Some(document_profiles)) => {
+                    document_profiles.iter().any(|document_profile| {
+                        match document_profile.strip_prefix('!') {
+                            Some(excluded_profile) => excluded_profile != profile,
+                            None => document_profile == profile,
+                        }
+                    })
+                }
+                (None, Some(_)) => !multi_document,
+                // documents without profile are always active
+                (_, None) => true,
+            }
+        });
+        let mut nodes = active_documents.next().unwrap_or_default();
+        for document in active_documents {
+            nodes.override_with(&document, &args.array_merge);
+        }
+        Ok(nodes)
+    }
+
+    /// Profiles a document is activated for, the Spring Boot 2.4 key wins over the legacy one
+    fn document_profiles(document: &Nodes) -> Option<Vec<String>> {
+        let profiles = document
+            .find("spring.config.activate.on-profile")
+            .or_else(|| document.find("spring.profiles"))?;
+        Some(
+            profiles
+                .value
+                .to_string()
+                .split(',')
+                .map(|profile| profile.trim().to_string())
+                .collect(),
+        )
+    }
+    pub(crate) fn convert_yaml_values_to_nodes(yaml_value: &Value) -> Result<Nodes, ProconError> {
         let mut nodes: Nodes = Nodes::new();
         match yaml_value {
             Value::Mapping(ref obj) => {
                 for (map_key, map_value) in obj.iter() {
                     let mut parent =
-                        Self::yaml_to_node(map_key.as_str().unwrap(), &map_value, None, 0).unwrap();
+                        Self::yaml_to_node(map_key.as_str().unwrap(), map_value, None, 0).unwrap();
                     nodes.merge(&mut parent);
                 }
             }
             Value::Sequence(yaml_value) => {
                 let mut parent = Node::new_from_name("");
-                let mut children: Vec<String> = vec![];
-                for value in yaml_value.iter() {
-                    let string_value = Self::yaml_value_to_string(value);
-                    children.push(string_value);
-                }
-                parent.value = NodeType::ARRAY(children);
+                parent.value =
+                    NodeType::ARRAY(Self::yaml_sequence_to_nodes(yaml_value, &mut parent, 0));
                 nodes.merge(&mut parent);
             }
             _ => eprintln!("not valid yaml"),
@@ -51,6 +556,14 @@ impl YamlFileReader {
         parent: Option<&mut Node>,
         level: usize,
     ) -> Option<Node> {
+        // local tags like `!Ref` are kept next to the tagged value, the parser drops core tags
+        // like `!!binary`, they are restored from the source by `keep_core_tags`
+        if let Value::Tagged(tagged) = value {
+            let mut tagged_node = Self::yaml_to_node(key, &tagged.value, parent, level)?;
+            tagged_node.tag = Some(tagged.tag.to_string());
+            return Some(tagged_node);
+        }
+
         let mut new_node: Node;
         if level == 0 {
             new_node = Node::new_from_name(key);
@@ -59,25 +572,25 @@ impl YamlFileReader {
         }
 
         let new_node_option = match value {
+            // yaml scalars are already resolved by the parser, no need to guess the type
             Value::Bool(yaml_value) => {
-                new_node.value = NodeType::parse(&yaml_value.to_string());
+                new_node.value = NodeType::BOOLEAN(*yaml_value);
                 Some(new_node)
             }
             Value::Number(yaml_value) => {
-                new_node.value = NodeType::parse(&yaml_value.to_string());
+                new_node.value = NodeType::NUMERIC(yaml_value.to_string());
                 Some(new_node)
             }
             Value::String(yaml_value) => {
-                new_node.value = NodeType::parse(yaml_value);
+                new_node.value = NodeType::STRING(yaml_value.clone());
                 Some(new_node)
             }
             Value::Sequence(yaml_value) => {
-                let mut children: Vec<String> = vec![];
-                for value in yaml_value.iter() {
-                    let string_value = Self::yaml_value_to_string(value);
-                    children.push(string_value);
-                }
-                new_node.value = NodeType::ARRAY(children);
+                new_node.value = NodeType::ARRAY(Self::yaml_sequence_to_nodes(
+                    yaml_value,
+                    &mut new_node,
+                    level,
+                ));
                 Some(new_node)
             }
             Value::Mapping(yaml_value) => {
@@ -89,26 +602,30 @@ impl YamlFileReader {
                         Some(&mut new_node),
                         level + 1,
                     );
-                    if child_node.is_some() {
-                        children.push(child_node.unwrap());
+                    if let Some(child_node) = child_node {
+                        children.push(child_node);
                     }
                 }
                 new_node.children = children;
                 Some(new_node)
             }
-            Value::Tagged(_) => None,
-            Value::Null => None,
+            Value::Tagged(_) => unreachable!("tagged values are unwrapped above"),
+            Value::Null => {
+                new_node.value = NodeType::NULL;
+                Some(new_node)
+            }
         };
         new_node_option
     }
 
-    fn yaml_value_to_string(value: &Value) -> String {
-        if value.is_number() {
-            return value.as_f64().unwrap().to_string();
-        }
-        if value.is_bool() {
-            return value.as_bool().unwrap().to_string();
+    fn yaml_sequence_to_nodes(sequence: &[Value], parent: &mut Node, level: usize) -> Vec<Node> {
+        let mut elements: Vec<Node> = vec![];
+        for (index, value) in sequence.iter().enumerate() {
+            let element = Self::yaml_to_node(&index.to_string(), value, Some(parent), level + 1);
+            if let Some(element) = element {
+                elements.push(element);
+            }
         }
-        value.as_str().unwrap().to_string()
+        elements
     }
 }
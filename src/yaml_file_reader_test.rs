@@ -2,6 +2,7 @@
 mod tests {
     use serde_yaml::Value;
 
+    use crate::node::NodeType::{NULL, NUMERIC, STRING};
     use crate::yaml_file_reader::YamlFileReader;
 
     #[test]
@@ -19,17 +20,17 @@ reader:
 
         let writer_node = nodes.get(0).unwrap();
         assert_eq!("writer", writer_node.name);
-        let datasource_node = writer_node.children.get(0).unwrap();
+        let datasource_node = writer_node.children.first().unwrap();
         assert_eq!("datasource", datasource_node.name);
-        let jdbc_url_node = datasource_node.children.get(0).unwrap();
+        let jdbc_url_node = datasource_node.children.first().unwrap();
         assert_eq!("jdbc-url", jdbc_url_node.name);
         assert_eq!("localhost", jdbc_url_node.value.to_string());
 
         let reader_node = nodes.get(1).unwrap();
         assert_eq!("reader", reader_node.name);
-        let datasource_node = reader_node.children.get(0).unwrap();
+        let datasource_node = reader_node.children.first().unwrap();
         assert_eq!("datasource", datasource_node.name);
-        let jdbc_url_node = datasource_node.children.get(0).unwrap();
+        let jdbc_url_node = datasource_node.children.first().unwrap();
         assert_eq!("jdbc-url", jdbc_url_node.name);
         assert_eq!("localhost", jdbc_url_node.value.to_string());
     }
@@ -46,9 +47,9 @@ reader:
 
         let writer_node = nodes.get(0).unwrap();
         assert_eq!("writer", writer_node.name);
-        let datasource_node = writer_node.children.get(0).unwrap();
+        let datasource_node = writer_node.children.first().unwrap();
         assert_eq!("datasource", datasource_node.name);
-        let jdbc_url_node = datasource_node.children.get(0).unwrap();
+        let jdbc_url_node = datasource_node.children.first().unwrap();
         assert_eq!("jdbc-url", jdbc_url_node.name);
         assert_eq!("localhost", jdbc_url_node.value.to_string());
     }
@@ -107,4 +108,188 @@ reader:
         let content = String::from("");
         let _yaml_data: Value = serde_yaml::from_str(&content).expect("Unable to parse");
     }
+
+    #[test]
+    fn convert_yaml_values_to_nodes_quoted_strings() {
+        let content = String::from("flag: \"true\"\nzip: \"08\"\nversion: '1.10'");
+        let yaml_data: Value = serde_yaml::from_str(&content).expect("Unable to parse");
+        let nodes = YamlFileReader::convert_yaml_values_to_nodes(&yaml_data).unwrap();
+
+        assert_eq!(STRING("true".to_string()), nodes.get(0).unwrap().value);
+        assert_eq!(STRING("08".to_string()), nodes.get(1).unwrap().value);
+        assert_eq!(STRING("1.10".to_string()), nodes.get(2).unwrap().value);
+    }
+
+    #[test]
+    fn convert_yaml_values_to_nodes_null() {
+        let content = String::from("tilde: ~\nword: null");
+        let yaml_data: Value = serde_yaml::from_str(&content).expect("Unable to parse");
+        let nodes = YamlFileReader::convert_yaml_values_to_nodes(&yaml_data).unwrap();
+
+        assert_eq!(NULL, nodes.get(0).unwrap().value);
+        assert_eq!(NULL, nodes.get(1).unwrap().value);
+    }
+
+    #[test]
+    fn yaml_line_numbers() {
+        let content = "server:\n  port: 8080\nservers:\n  - host: alpha\n    port: 1\n  - [a, b]\n  - plain\n";
+        let line_numbers = YamlFileReader::line_numbers(content);
+
+        assert_eq!(Some(&1), line_numbers.get("server"));
+        assert_eq!(Some(&2), line_numbers.get("server.port"));
+        assert_eq!(Some(&4), line_numbers.get("servers[0]"));
+        assert_eq!(Some(&5), line_numbers.get("servers[0].port"));
+        assert_eq!(Some(&6), line_numbers.get("servers[1][1]"));
+        assert_eq!(Some(&7), line_numbers.get("servers[2]"));
+    }
+
+    #[test]
+    fn yaml_parse_documents() {
+        let content = "first: 1\n---\nsecond: 2\n---\n---\nthird: 3\n";
+        let documents = YamlFileReader::parse_documents(content).unwrap();
+
+        assert_eq!(3, documents.len());
+        assert_eq!("second", documents[1].get(0).unwrap().name);
+        assert_eq!("third", documents[2].get(0).unwrap().name);
+        assert!(YamlFileReader::parse_documents("a: [\n---\nb: 1").is_err());
+    }
+
+    #[test]
+    fn yaml_merge_keys() {
+        let content = "base: &base\n  a: 1\n  b: 2\nmerged:\n  a: 0\n  <<: *base\n  c: 3\n";
+        let documents = YamlFileReader::parse_documents(content).unwrap();
+        let merged = documents[0].find("merged").unwrap();
+
+        let names: Vec<&str> = merged
+            .children
+            .iter()
+            .map(|child| child.name.as_str())
+            .collect();
+        assert_eq!(vec!["a", "b", "c"], names);
+        assert_eq!("0", merged.children[0].value.to_string());
+    }
+
+    #[test]
+    fn yaml_merge_key_scalar() {
+        assert!(YamlFileReader::parse_documents("merged:\n  <<: 1\n").is_err());
+    }
+
+    #[test]
+    fn convert_yaml_values_to_nodes_tagged_values() {
+        let content = String::from(
+            "bucket: !Ref MyBucket
+arn: !GetAtt [MyBucket, Arn]
+logo: !!binary aGVsbG8=
+size: !!int 3",
+        );
+        let documents = YamlFileReader::parse_documents(&content).unwrap();
+        let nodes = documents.first().unwrap();
+
+        let bucket_node = nodes.get(0).unwrap();
+        assert_eq!(Some("!Ref".to_string()), bucket_node.tag);
+        assert_eq!(STRING("MyBucket".to_string()), bucket_node.value);
+        let arn_node = nodes.get(1).unwrap();
+        assert_eq!(Some("!GetAtt".to_string()), arn_node.tag);
+        assert_eq!("MyBucket,Arn", arn_node.value.to_string());
+        let logo_node = nodes.get(2).unwrap();
+        assert_eq!(Some("!!binary".to_string()), logo_node.tag);
+        assert_eq!(STRING("aGVsbG8=".to_string()), logo_node.value);
+        let size_node = nodes.get(3).unwrap();
+        assert_eq!(None, size_node.tag);
+        assert_eq!(NUMERIC("3".to_string()), size_node.value);
+    }
+
+    #[test]
+    fn yaml_comments() {
+        let content = "# server settings
+server:
+  # the port
+  port: 8080 # default
+  host: \"a # b\"
+script: |
+  # not a comment
+  echo
+# last
+list:
+  - a # first
+";
+        let mut nodes = YamlFileReader::parse_documents(content).unwrap().remove(0);
+        YamlFileReader::attach_comments(&mut nodes, content);
+
+        assert_eq!(
+            vec!["server settings"],
+            nodes.find("server").unwrap().comments
+        );
+        let port = nodes.find("server.port").unwrap();
+        assert_eq!(vec!["the port"], port.comments);
+        assert_eq!(Some("default".to_string()), port.trailing_comment);
+        assert_eq!(None, nodes.find("server.host").unwrap().trailing_comment);
+        assert!(nodes.find("script").unwrap().comments.is_empty());
+        assert_eq!(vec!["last"], nodes.find("list").unwrap().comments);
+        assert_eq!(
+            Some("first".to_string()),
+            nodes.find("list[0]").unwrap().trailing_comment
+        );
+    }
+
+    #[test]
+    fn yaml_numbers_keep_lexeme() {
+        let content = "version: 1.10\nexp: 1e5\nhex: 0x1F\nlist: [1.50, 7]\n---\nversion: 2.10\n";
+        let documents = YamlFileReader::parse_documents(content).unwrap();
+
+        let nodes = &documents[0];
+        assert_eq!(
+            NUMERIC("1.10".to_string()),
+            nodes.find("version").unwrap().value
+        );
+        assert_eq!(NUMERIC("1e5".to_string()), nodes.find("exp").unwrap().value);
+        // other notations are no decimal numbers and keep the value of the parser
+        assert_eq!(NUMERIC("31".to_string()), nodes.find("hex").unwrap().value);
+        assert_eq!(
+            NUMERIC("1.50".to_string()),
+            nodes.find("list[0]").unwrap().value
+        );
+        assert_eq!(
+            NUMERIC("7".to_string()),
+            nodes.find("list[1]").unwrap().value
+        );
+
+        let nodes = &documents[1];
+        assert_eq!(
+            NUMERIC("2.10".to_string()),
+            nodes.find("version").unwrap().value
+        );
+    }
+
+    #[test]
+    fn yaml_numbers_keep_lexeme_through_aliases() {
+        let content = "defaults: &defaults
+  timeout: 1.10
+  retries: 3
+  cert: !!binary aGVsbG8=
+svc:
+  retries: 5
+  <<: *defaults
+other:
+  <<: [*defaults]
+ratio: &ratio 2.50
+copy: *ratio
+list: &list [1.50, 2.0]
+copied: *list
+";
+        let documents = YamlFileReader::parse_documents(content).unwrap();
+        let nodes = &documents[0];
+
+        let value = |key: &str| nodes.find(key).unwrap().value.clone();
+        assert_eq!(NUMERIC("1.10".to_string()), value("svc.timeout"));
+        assert_eq!(NUMERIC("5".to_string()), value("svc.retries"));
+        assert_eq!(
+            Some("!!binary".to_string()),
+            nodes.find("svc.cert").unwrap().tag
+        );
+        assert_eq!(NUMERIC("1.10".to_string()), value("other.timeout"));
+        assert_eq!(NUMERIC("2.50".to_string()), value("copy"));
+        assert_eq!(NUMERIC("1.50".to_string()), value("copied[0]"));
+        assert_eq!(NUMERIC("2.0".to_string()), value("copied[1]"));
+    }
 }
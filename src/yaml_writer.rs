@@ -0,0 +1,380 @@
+use yaml_rust::Yaml;
+
+use crate::node::{Node, NodeType};
+use crate::nodes::{MultilineStyle, Nodes, QuoteStyle};
+
+#[cfg(test)]
+#[path = "./yaml_writer_test.rs"]
+mod yaml_writer_test;
+
+/// Options of the yaml writer
+#[derive(Debug, Clone)]
+pub(crate) struct YamlStyle {
+    /// spaces per nesting level
+    pub(crate) indent: usize,
+    /// arrays of scalars up to this length are written in flow style like `[a, b]`
+    pub(crate) flow_arrays: Option<usize>,
+    pub(crate) quote: QuoteStyle,
+    pub(crate) multiline: MultilineStyle,
+    /// the document starts with `---`
+    pub(crate) document_marker: bool,
+}
+
+impl Default for YamlStyle {
+    fn default() -> Self {
+        YamlStyle {
+            indent: 2,
+            flow_arrays: None,
+            quote: QuoteStyle::Ambiguous,
+            multiline: MultilineStyle::Quoted,
+            document_marker: true,
+        }
+    }
+}
+
+/// Block style yaml emitter for the node tree
+///
+/// Follows the output of the yaml-rust emitter, which can not write tags like `!Ref`
+pub(crate) struct YamlWriter<'a> {
+    content: String,
+    /// column of the entries of the current collection
+    column: usize,
+    style: &'a YamlStyle,
+    /// end of the last block scalar, its final line break is part of the value
+    block_scalar_end: usize,
+}
+
+impl<'a> YamlWriter<'a> {
+    pub(crate) fn write(nodes: &Nodes, style: &'a YamlStyle) -> String {
+        let mut writer = YamlWriter {
+            content: String::new(),
+            column: 0,
+            style,
+            block_scalar_end: 0,
+        };
+        if style.document_marker {
+            writer.content.push_str("---\n");
+        }
+        // root list treatment
+        match nodes.iter().find(|node| node.name.is_empty()) {
+            Some(root_list) => {
+                writer.emit_comments(root_list);
+                writer.emit_node(root_list)
+            }
+            None => {
+                let children: Vec<&Node> = nodes.iter().collect();
+                writer.emit_mapping(&children);
+            }
+        }
+        if writer.block_scalar_end == writer.content.len() && !writer.content.is_empty() {
+            writer.content.push('\n');
+        }
+        for comment in &nodes.trailing_comments {
+            if !writer.content.is_empty() && !writer.content.ends_with('\n') {
+                writer.content.push('\n');
+            }
+            writer.content.push_str(&comment_text(comment));
+        }
+        writer.content
+    }
+
+    /// Single line flow style like `[a, b]` or `{key: value}`
+    pub(crate) fn flow(node: &Node) -> String {
+        flow(node, &QuoteStyle::Ambiguous)
+    }
+
+    fn write_indent(&mut self, column: usize) {
+        self.content.push_str(&" ".repeat(column));
+    }
+
+    /// Column of a nested collection, after a `-` at least two to leave room for the space
+    fn nested_column(&self, inline: bool) -> usize {
+        match inline {
+            true => self.column + self.style.indent.max(2),
+            false => self.column + self.style.indent,
+        }
+    }
+
+    fn emit_node(&mut self, node: &Node) {
+        match &node.value {
+            NodeType::ARRAY(elements) => self.emit_sequence(elements),
+            NodeType::NONE => self.emit_mapping(&node.children.iter().collect::<Vec<&Node>>()),
+            _ => self.content.push_str(&scalar(node, &self.style.quote)),
+        }
+    }
+
+    fn emit_sequence(&mut self, elements: &[Node]) {
+        if elements.is_empty() {
+            self.content.push_str("[]");
+            return;
+        }
+        for (index, element) in elements.iter().enumerate() {
+            if index > 0 {
+                self.content.push('\n');
+                self.write_indent(self.column);
+            }
+            self.emit_comments(element);
+            self.content.push('-');
+            self.emit_value(true, element);
+        }
+    }
+
+    fn emit_mapping(&mut self, children: &[&Node]) {
+        if children.is_empty() {
+            self.content.push_str("{}");
+            return;
+        }
+        for (index, child) in children.iter().enumerate() {
+            if index > 0 {
+                self.content.push('\n');
+                self.write_indent(self.column);
+            }
+            self.emit_comments(child);
+            self.content.push_str(&key(&child.name));
+            self.content.push(':');
+            self.emit_value(false, child);
+        }
+    }
+
+    /// Value after a `:` or `-`, sequence elements start inline with their first entry
+    fn emit_value(&mut self, inline: bool, node: &Node) {
+        let collection = match &node.value {
+            NodeType::ARRAY(elements) => !elements.is_empty(),
+            NodeType::NONE => !node.children.is_empty(),
+            _ => false,
+        };
+        if let Some(tag) = &node.tag {
+            self.content.push(' ');
+            self.content.push_str(tag);
+        }
+        let column = self.column;
+        if let Some(flow) = self.flow_array(node) {
+            self.content.push(' ');
+            self.content.push_str(&flow);
+            self.emit_trailing_comment(node);
+        } else if let Some((header, lines)) = self.block_scalar(node) {
+            self.content.push(' ');
+            self.content.push_str(&header);
+            self.emit_trailing_comment(node);
+            let body_column = self.nested_column(inline);
+            for line in lines {
+                self.content.push('\n');
+                // empty lines get no indent, trailing spaces would be part of the value
+                if !line.is_empty() {
+                    self.write_indent(body_column);
+                    self.content.push_str(line);
+                }
+            }
+            self.block_scalar_end = self.content.len();
+        }
+        // tagged collections always start on the next line
+        else if collection && (!inline || node.tag.is_some()) {
+            self.emit_trailing_comment(node);
+            self.column = self.nested_column(inline);
+            self.content.push('\n');
+            self.write_indent(self.column);
+            self.emit_node(node);
+        } else if collection {
+            // sequence elements are padded to the column of their entries
+            self.column = self.nested_column(inline);
+            self.write_indent(self.column - column - 1);
+            self.emit_node(node);
+        } else {
+            self.content.push(' ');
+            self.emit_node(node);
+            self.emit_trailing_comment(node);
+        }
+        self.column = column;
+    }
+
+    /// Flow style of short arrays of scalars, comments of the elements would be lost
+    fn flow_array(&self, node: &Node) -> Option<String> {
+        let max_length = self.style.flow_arrays?;
+        let NodeType::ARRAY(elements) = &node.value else {
+            return None;
+        };
+        let scalars = elements.iter().all(|element| {
+            element.value.is_scalar()
+                && element.tag.is_none()
+                && element.comments.is_empty()
+                && element.trailing_comment.is_none()
+        });
+        if elements.is_empty() || !scalars {
+            return None;
+        }
+        let elements: Vec<String> = elements
+            .iter()
+            .map(|element| flow(element, &self.style.quote))
+            .collect();
+        let flow = ["[", &elements.join(", "), "]"].concat();
+        (flow.chars().count() <= max_length).then_some(flow)
+    }
+
+    /// Header like `|-` and the lines of a string written as block scalar
+    ///
+    /// Strings with other control characters or leading whitespace stay quoted, folded strings
+    /// with indented lines are written literal
+    fn block_scalar<'n>(&self, node: &'n Node) -> Option<(String, Vec<&'n str>)> {
+        let NodeType::STRING(value) = &node.value else {
+            return None;
+        };
+        let text = value.trim_end_matches('\n');
+        let quoted = self.style.multiline == MultilineStyle::Quoted
+            || !value.contains('\n')
+            || text.trim_start_matches('\n').starts_with([' ', '\t'])
+            || text.trim_start_matches('\n').is_empty()
+            || value.contains(|character: char| {
+                character.is_control() && character != '\n' && character != '\t'
+            });
+        if quoted {
+            return None;
+        }
+        let chomping = match value.len() - text.len() {
+            0 => "-",
+            1 => "",
+            _ => "+",
+        };
+        let mut lines: Vec<&str> = text.split('\n').collect();
+        let folded = self.style.multiline == MultilineStyle::Folded
+            && !lines.iter().any(|line| line.starts_with([' ', '\t']));
+        let indicator = match folded {
+            true => {
+                // a single line break between two lines is read as a space
+                let mut folded_lines = vec![];
+                for (index, line) in lines.iter().enumerate() {
+                    folded_lines.push(*line);
+                    if !line.is_empty() && index < lines.len() - 1 {
+                        folded_lines.push("");
+                    }
+                }
+                lines = folded_lines;
+                ">"
+            }
+            false => "|",
+        };
+        // kept line breaks after the last line, the final one follows the block
+        let line_breaks = value.len() - text.len();
+        lines.extend(std::iter::repeat_n("", line_breaks.saturating_sub(1)));
+        Some(([indicator, chomping].concat(), lines))
+    }
+
+    /// Comment lines above the key, each followed by the indent of the key
+    fn emit_comments(&mut self, node: &Node) {
+        for comment in &node.comments {
+            self.content.push_str(&comment_text(comment));
+            self.content.push('\n');
+            self.write_indent(self.column);
+        }
+    }
+
+    fn emit_trailing_comment(&mut self, node: &Node) {
+        if let Some(comment) = &node.trailing_comment {
+            self.content.push(' ');
+            self.content.push_str(&comment_text(comment));
+        }
+    }
+}
+
+fn flow(node: &Node, quote: &QuoteStyle) -> String {
+    let mut content = String::new();
+    if let Some(tag) = &node.tag {
+        content.push_str(tag);
+        content.push(' ');
+    }
+    match &node.value {
+        NodeType::ARRAY(elements) => {
+            let elements: Vec<String> = elements
+                .iter()
+                .map(|element| flow(element, quote))
+                .collect();
+            content.push_str(&["[", &elements.join(", "), "]"].concat());
+        }
+        NodeType::NONE => {
+            let entries: Vec<String> = node
+                .children
+                .iter()
+                .map(|child| [key(&child.name), ": ".to_string(), flow(child, quote)].concat())
+                .collect();
+            content.push_str(&["{", &entries.join(", "), "}"].concat());
+        }
+        _ => content.push_str(&scalar(node, quote)),
+    }
+    content
+}
+
+fn comment_text(comment: &str) -> String {
+    if comment.is_empty() {
+        return String::from("#");
+    }
+    ["# ", comment].concat()
+}
+
+/// Keys are always strings, keys like `null` or `8080` are quoted like string values
+fn key(name: &str) -> String {
+    match need_quotes(name) {
+        true => escape(name),
+        false => name.to_string(),
+    }
+}
+
+fn scalar(node: &Node, quote: &QuoteStyle) -> String {
+    match (node.into(), quote) {
+        (Yaml::String(value), QuoteStyle::Always) => escape(&value),
+        (yaml, _) => scalar_yaml(&yaml),
+    }
+}
+
+fn scalar_yaml(yaml: &Yaml) -> String {
+    match yaml {
+        Yaml::String(value) if need_quotes(value) => escape(value),
+        Yaml::String(value) => value.clone(),
+        Yaml::Boolean(value) => value.to_string(),
+        Yaml::Integer(value) => value.to_string(),
+        Yaml::Real(value) => value.clone(),
+        _ => String::from("~"),
+    }
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::from("\"");
+    for character in value.chars() {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\x08' => escaped.push_str("\\b"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\x0c' => escaped.push_str("\\f"),
+            '\r' => escaped.push_str("\\r"),
+            '\0'..='\x1f' | '\x7f' => escaped.push_str(&format!("\\u{:04x}", character as u32)),
+            _ => escaped.push(character),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+/// Strings which would be read as another type or contain yaml syntax are quoted, the
+/// booleans of YAML 1.1 like `yes` or `y` are quoted for older consumers
+fn need_quotes(value: &str) -> bool {
+    value.is_empty()
+        || value.starts_with(' ')
+        || value.ends_with(' ')
+        || value.starts_with(['&', '*', '?', '|', '-', '<', '>', '=', '!', '%', '@', '.'])
+        || value.contains([
+            ':', '{', '}', '[', ']', ',', '#', '`', '"', '\'', '\\', '\t', '\n', '\r',
+        ])
+        || value.contains(|character: char| character.is_ascii_control())
+        || [
+            "yes", "Yes", "YES", "no", "No", "NO", "True", "TRUE", "true", "False", "FALSE",
+            "false", "on", "On", "ON", "off", "Off", "OFF", "y", "Y", "n", "N", "null", "Null",
+            "NULL", "~",
+        ]
+        .contains(&value)
+        || value.parse::<f64>().is_ok()
+        // any other plain scalar resolved to a number like `0o17` or `.inf`
+        || !matches!(
+            serde_yaml::from_str::<serde_yaml::Value>(value),
+            Ok(serde_yaml::Value::String(_))
+        )
+}
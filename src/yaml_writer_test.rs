@@ -0,0 +1,324 @@
+#[cfg(test)]
+mod tests {
+    use yaml_rust::{Yaml, YamlEmitter, YamlLoader};
+
+    use crate::node::{Node, NodeType};
+    use crate::nodes::{MultilineStyle, Nodes, QuoteStyle};
+    use crate::yaml_file_reader::YamlFileReader;
+    use crate::yaml_writer::{YamlStyle, YamlWriter};
+
+    fn parse(content: &str) -> Nodes {
+        let mut documents = YamlFileReader::parse_documents(content).unwrap();
+        documents.remove(0)
+    }
+
+    fn emit(yaml: &Yaml) -> String {
+        let mut content = String::new();
+        YamlEmitter::new(&mut content).dump(yaml).unwrap();
+        content
+    }
+
+    #[test]
+    fn write_like_yaml_rust() {
+        let content = "server:
+  port: 8080
+  host: \"127.0.0.1\"
+  enabled: true
+  ratio: 0.5
+  empty: ~
+  quoted: \"yes\"
+  text: \"a: b\"
+servers:
+  - name: a
+    ports:
+      - 80
+      - 443
+  - name: b
+    ports: []
+nested:
+  - - 1
+    - 2
+";
+        let nodes = parse(content);
+        let expected = emit(&YamlLoader::load_from_str(content).unwrap()[0]);
+
+        assert_eq!(expected, YamlWriter::write(&nodes, &YamlStyle::default()));
+    }
+
+    #[test]
+    fn write_root_list() {
+        let nodes = parse("- a\n- b: c\n  d: e\n");
+
+        assert_eq!(
+            "---\n- a\n- b: c\n  d: e",
+            YamlWriter::write(&nodes, &YamlStyle::default())
+        );
+    }
+
+    #[test]
+    fn write_tagged_values() {
+        let nodes = parse(
+            "bucket: !Ref MyBucket
+arn: !GetAtt [MyBucket, Arn]
+logo: !!binary aGVsbG8=
+policy: !Sub
+  name: ${AWS::Region}
+",
+        );
+
+        assert_eq!(
+            "---
+bucket: !Ref MyBucket
+arn: !GetAtt
+  - MyBucket
+  - Arn
+logo: !!binary aGVsbG8=
+policy: !Sub
+  name: \"${AWS::Region}\"",
+            YamlWriter::write(&nodes, &YamlStyle::default())
+        );
+    }
+
+    #[test]
+    fn flow() {
+        let nodes = parse("arn: !GetAtt [MyBucket, Arn]\npolicy:\n  name: a\n  ids: [1, 2]\n");
+        let node: &Node = nodes.get(0).unwrap();
+        assert_eq!("!GetAtt [MyBucket, Arn]", YamlWriter::flow(node));
+        let node: &Node = nodes.get(1).unwrap();
+        assert_eq!("{name: a, ids: [1, 2]}", YamlWriter::flow(node));
+    }
+
+    #[test]
+    fn write_comments() {
+        let mut nodes = parse("server:\n  port: 8080\n  hosts:\n    - a\n    - b\n");
+        nodes.find_mut("server").unwrap().comments = vec!["settings".to_string()];
+        let port = nodes.find_mut("server.port").unwrap();
+        port.comments = vec!["the port".to_string(), "".to_string()];
+        port.trailing_comment = Some("default".to_string());
+        nodes.find_mut("server.hosts").unwrap().trailing_comment = Some("hosts".to_string());
+        nodes.find_mut("server.hosts[1]").unwrap().comments = vec!["backup".to_string()];
+
+        assert_eq!(
+            "---
+# settings
+server:
+  # the port
+  #
+  port: 8080 # default
+  hosts: # hosts
+    - a
+    # backup
+    - b",
+            YamlWriter::write(&nodes, &YamlStyle::default())
+        );
+    }
+
+    #[test]
+    fn write_trailing_comments() {
+        let mut nodes = parse("port: 8080\n");
+        nodes.trailing_comments = vec!["end".to_string(), "".to_string()];
+
+        assert_eq!(
+            "---\nport: 8080\n# end\n#",
+            YamlWriter::write(&nodes, &YamlStyle::default())
+        );
+    }
+
+    #[test]
+    fn write_nan_and_infinity() {
+        let mut nodes = parse("limit: 1\nfloor: 1\nmissing: 1\n");
+        nodes.find_mut("limit").unwrap().value = NodeType::NUMERIC("inf".to_string());
+        nodes.find_mut("floor").unwrap().value = NodeType::NUMERIC("-inf".to_string());
+        nodes.find_mut("missing").unwrap().value = NodeType::NUMERIC("nan".to_string());
+
+        let written = YamlWriter::write(&nodes, &YamlStyle::default());
+        assert_eq!("---\nlimit: .inf\nfloor: -.inf\nmissing: .nan", written);
+        let read_back: serde_yaml::Value = serde_yaml::from_str(&written).unwrap();
+        assert_eq!(Some(f64::INFINITY), read_back["limit"].as_f64());
+        assert_eq!(Some(f64::NEG_INFINITY), read_back["floor"].as_f64());
+        assert!(read_back["missing"].as_f64().unwrap().is_nan());
+    }
+
+    /// The written yaml reads back to the same values
+    fn assert_round_trip(content: &str, written: &str) {
+        let expected: serde_yaml::Value = serde_yaml::from_str(content).unwrap();
+        let actual: serde_yaml::Value = serde_yaml::from_str(written).unwrap();
+        assert_eq!(expected, actual, "{}", written);
+    }
+
+    #[test]
+    fn write_indent() {
+        let content = "servers:\n  - name: a\n    ports: [80, 443]\n  - - 1\n    - 2\n";
+        let nodes = parse(content);
+
+        let style = YamlStyle {
+            indent: 4,
+            ..YamlStyle::default()
+        };
+        let written = YamlWriter::write(&nodes, &style);
+        assert_eq!(
+            "---
+servers:
+    -   name: a
+        ports:
+            - 80
+            - 443
+    -   - 1
+        - 2",
+            written
+        );
+        assert_round_trip(content, &written);
+
+        let style = YamlStyle {
+            indent: 1,
+            ..YamlStyle::default()
+        };
+        assert_round_trip(content, &YamlWriter::write(&nodes, &style));
+    }
+
+    #[test]
+    fn write_flow_arrays() {
+        let nodes = parse("short: [a, b]\nlong: [alpha, beta, gamma]\nobjects: [{a: 1}]\n");
+        let style = YamlStyle {
+            flow_arrays: Some(10),
+            ..YamlStyle::default()
+        };
+
+        assert_eq!(
+            "---
+short: [a, b]
+long:
+  - alpha
+  - beta
+  - gamma
+objects:
+  - a: 1",
+            YamlWriter::write(&nodes, &style)
+        );
+    }
+
+    #[test]
+    fn write_quote_always() {
+        let nodes = parse("name: demo\nport: 8080\nid: \"8080\"\nenabled: true\n");
+        let style = YamlStyle {
+            quote: QuoteStyle::Always,
+            document_marker: false,
+            ..YamlStyle::default()
+        };
+
+        assert_eq!(
+            "name: \"demo\"\nport: 8080\nid: \"8080\"\nenabled: true",
+            YamlWriter::write(&nodes, &style)
+        );
+    }
+
+    #[test]
+    fn write_quote_strings_read_as_other_types() {
+        let values = [
+            "0o17", "0x1F", "1e5", ".inf", "-.Inf", ".NaN", "y", "N", "yes", "~", "12", "1.10",
+        ];
+        let mut nodes = Nodes::new();
+        for (index, value) in values.iter().enumerate() {
+            let mut node = Node::new_from_name(&format!("key{}", index));
+            node.value = NodeType::STRING(value.to_string());
+            nodes.push(node);
+        }
+
+        let written = YamlWriter::write(&nodes, &YamlStyle::default());
+        let read_back: serde_yaml::Value = serde_yaml::from_str(&written).unwrap();
+        for (index, value) in values.iter().enumerate() {
+            let key = format!("key{}", index);
+            assert_eq!(
+                Some(*value),
+                read_back[key.as_str()].as_str(),
+                "{}",
+                written
+            );
+        }
+        assert!(written.contains("key6: \"y\""));
+        assert!(written.contains("key7: \"N\""));
+    }
+
+    #[test]
+    fn write_keys_read_as_other_types() {
+        let content = "\"null\": a\n\"true\": b\n\"8080\": c\n\"1.10\": d\nnested:\n  \"~\": e\n";
+        let nodes = parse(content);
+
+        let written = YamlWriter::write(&nodes, &YamlStyle::default());
+        assert_eq!(
+            "---\n\"null\": a\n\"true\": b\n\"8080\": c\n\"1.10\": d\nnested:\n  \"~\": e",
+            written
+        );
+        assert_round_trip(content, &written);
+        let read_back = parse(&written);
+        assert_eq!(
+            vec!["null", "true", "8080", "1.10", "nested"],
+            read_back
+                .iter()
+                .map(|node| node.name.as_str())
+                .collect::<Vec<&str>>()
+        );
+
+        let nodes = parse("map:\n  \"null\": a\n  \"8080\": b\n");
+        assert_eq!(
+            "{\"null\": a, \"8080\": b}",
+            YamlWriter::flow(nodes.get(0).unwrap())
+        );
+    }
+
+    #[test]
+    fn write_multiline() {
+        let content = "strip: \"first\\nsecond\"
+clip: \"first\\nsecond\\n\"
+keep: \"first\\n\\nsecond\\n\\n\"
+indented: \"first\\n  second\\n\"
+leading: \"  first\\nsecond\"
+list:
+  - \"a\\nb\"
+last: \"end\\n\"
+";
+        let nodes = parse(content);
+
+        let literal = YamlStyle {
+            multiline: MultilineStyle::Literal,
+            ..YamlStyle::default()
+        };
+        let written = YamlWriter::write(&nodes, &literal);
+        assert_eq!(
+            "---
+strip: |-
+  first
+  second
+clip: |
+  first
+  second
+keep: |+
+  first
+
+  second
+
+indented: |
+  first
+    second
+leading: \"  first\\nsecond\"
+list:
+  - |-
+    a
+    b
+last: |
+  end
+",
+            written
+        );
+        assert_round_trip(content, &written);
+
+        let folded = YamlStyle {
+            multiline: MultilineStyle::Folded,
+            ..YamlStyle::default()
+        };
+        let written = YamlWriter::write(&nodes, &folded);
+        assert!(written.contains("strip: >-\n  first\n\n  second\n"));
+        assert!(written.contains("indented: |\n"));
+        assert_round_trip(content, &written);
+    }
+}
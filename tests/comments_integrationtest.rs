@@ -0,0 +1,99 @@
+use procon::nodes_writer::{to_json, to_properties, to_yaml};
+use procon::parse_input_file;
+use procon::property_file_reader::{Delimiter, PropertyFileReader};
+use procon::yaml_file_reader::YamlFileReader;
+
+use crate::common::create_args;
+
+mod common;
+
+#[test]
+fn properties_comments() {
+    let args = create_args(
+        Delimiter::Equals,
+        "tests/resources/properties/comments.properties",
+    );
+    let nodes = parse_input_file(&args).unwrap();
+
+    assert_eq!(
+        vec!["Application settings", "maintained by the ops team"],
+        nodes.find("server.port").unwrap().comments
+    );
+    assert_eq!(
+        vec!["datasource of the reporting database"],
+        nodes.find("db.url").unwrap().comments
+    );
+    assert!(nodes.find("db.user").unwrap().comments.is_empty());
+    assert_eq!(
+        vec!["end of the reporting settings", "  see the wiki"],
+        nodes.trailing_comments
+    );
+}
+
+#[test]
+fn yaml_comments() {
+    let args = create_args(Delimiter::Equals, "tests/resources/yaml/comments.yaml");
+    let nodes = parse_input_file(&args).unwrap();
+
+    assert_eq!(
+        vec!["Application settings"],
+        nodes.find("server").unwrap().comments
+    );
+    assert_eq!(
+        Some("do not change, see firewall rules".to_string()),
+        nodes.find("server.port").unwrap().trailing_comment
+    );
+    assert_eq!(
+        vec!["datasource of the reporting database"],
+        nodes.find("db.url").unwrap().comments
+    );
+    assert_eq!(
+        vec!["end of the reporting settings", "  see the wiki"],
+        nodes.trailing_comments
+    );
+}
+
+#[test]
+fn trailing_comments_written() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_procon"))
+        .args([
+            "--dry-run",
+            "properties",
+            "tests/resources/yaml/comments.yaml",
+        ])
+        .output()
+        .expect("Unable to run procon");
+    assert!(output.status.success());
+
+    let content = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        content.ends_with("db.user=report\n# end of the reporting settings\n#   see the wiki\n\n")
+    );
+}
+
+#[test]
+fn comments_written_by_all_writers() {
+    let mut args = create_args(Delimiter::Equals, "tests/resources/yaml/comments.yaml");
+    args.dry_run = true;
+    let nodes = parse_input_file(&args).unwrap();
+
+    assert!(to_yaml(&args, &nodes).is_ok());
+    assert!(to_properties(&args, &nodes).is_ok());
+    assert!(to_json(&args, &nodes).is_ok());
+}
+
+#[test]
+fn trailing_comment_round_trip() {
+    let args = create_args(Delimiter::Equals, "tests/resources/yaml/comments.yaml");
+    let nodes =
+        YamlFileReader::parse(&args, "server:\n  port: 8080 # the port\n  name: \"08\"\n").unwrap();
+    let content: String = nodes.iter().map(|node| node.to_properties(false)).collect();
+    assert_eq!("# the port\nserver.port=8080\nserver.name=08\n", content);
+
+    let nodes = PropertyFileReader::parse(&args, &content).unwrap();
+    assert_eq!(
+        vec!["the port".to_string()],
+        nodes.find("server.port").unwrap().comments
+    );
+    assert!(nodes.find("server.name").unwrap().comments.is_empty());
+}
@@ -1,29 +1,52 @@
+//! Helpers shared by the integration tests, not every test uses all of them
+
 use std::path::PathBuf;
 
 use clap_verbosity_flag::Verbosity;
 
 use procon::args::{Args, TargetFormat};
 use procon::node::Node;
-use procon::nodes::Nodes;
+use procon::nodes::{ArrayMerge, Nodes, TagPolicy};
 use procon::parse_input_file;
 use procon::property_file_reader::Delimiter;
 
+#[allow(dead_code)]
 pub fn create_args(delimiter: Delimiter, filename: &str) -> Args {
     let args: Args = Args {
         target_format: TargetFormat::Json {
-            property_delimiter: delimiter,
+            property_delimiter: Some(delimiter),
+            jsonc: false,
+            indent: 1,
+            compact: false,
+            sort_keys: false,
+            ascii: false,
+            flat: false,
+            ndjson: false,
             file: PathBuf::from(filename),
+            merge_files: vec![],
         },
         dry_run: false,
         from_property_file: false,
         from_yaml_file: false,
         from_json_file: false,
+        from_toml_file: false,
+        from_dotenv_file: false,
+        strict_numbers: false,
+        string_keys: vec![],
+        interpolate: false,
+        array_merge: ArrayMerge::Replace,
+        tagged_values: TagPolicy::Keep,
+        select: None,
+        document: None,
+        profile: None,
+        split: false,
         output_filename: None,
         verbose: Verbosity::new(0, 0),
     };
     args
 }
 
+#[allow(dead_code)]
 pub fn parse_test_file(delimiter: Delimiter, filename: &str) -> Nodes {
     let args = create_args(delimiter, filename);
     let mut nodes = parse_input_file(&args).unwrap();
@@ -31,6 +54,7 @@ pub fn parse_test_file(delimiter: Delimiter, filename: &str) -> Nodes {
     nodes
 }
 
+#[allow(dead_code)]
 pub fn assert_node(node: &Node, name: String, value: String) {
     assert_eq!(name, node.name);
     assert_eq!(value, node.value.to_string());
@@ -0,0 +1,51 @@
+use procon::nodes_diff::{diff, Difference};
+use procon::property_file_reader::Delimiter;
+
+use crate::common::parse_test_file;
+
+mod common;
+
+#[test]
+fn diff_same_meaning_across_formats() {
+    let nodes = parse_test_file(Delimiter::Equals, "tests/resources/diff/application.yaml");
+    let other_nodes = parse_test_file(
+        Delimiter::Equals,
+        "tests/resources/diff/application.properties",
+    );
+
+    assert!(diff(&nodes, &other_nodes).is_empty());
+}
+
+#[test]
+fn diff_changed_properties() {
+    let nodes = parse_test_file(Delimiter::Equals, "tests/resources/diff/application.yaml");
+    let other_nodes = parse_test_file(
+        Delimiter::Equals,
+        "tests/resources/diff/application-prod.properties",
+    );
+
+    let differences = diff(&nodes, &other_nodes);
+    assert_eq!(3, differences.len());
+    assert_eq!(
+        Difference::Changed {
+            key: String::from("server.port"),
+            value: String::from("8080"),
+            other_value: String::from("443"),
+        },
+        differences[1]
+    );
+    assert_eq!(
+        Difference::Removed {
+            key: String::from("server.hosts[1]"),
+            value: String::from("beta"),
+        },
+        differences[0]
+    );
+    assert_eq!(
+        Difference::Added {
+            key: String::from("logging.level.root"),
+            value: String::from("warn"),
+        },
+        differences[2]
+    );
+}
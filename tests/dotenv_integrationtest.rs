@@ -0,0 +1,88 @@
+use std::env;
+use std::path::PathBuf;
+
+use procon::args::TargetFormat;
+use procon::node::NodeType;
+use procon::nodes_writer::to_dotenv;
+use procon::parse_input_file;
+use procon::property_file_reader::Delimiter;
+use procon::yaml_file_reader::YamlFileReader;
+
+use crate::common::{create_args, parse_test_file};
+
+mod common;
+
+#[test]
+fn dotenv_file() {
+    let nodes = parse_test_file(Delimiter::Equals, "tests/resources/dotenv/application.env");
+
+    assert_eq!(
+        NodeType::STRING(String::from("jdbc:postgresql://localhost/app")),
+        nodes.find("db.url").unwrap().value
+    );
+    assert_eq!(
+        NodeType::STRING(String::from("admin")),
+        nodes.find("db.user").unwrap().value
+    );
+    assert_eq!(
+        NodeType::NUMERIC(String::from("8080")),
+        nodes.find("server.port").unwrap().value
+    );
+    assert_eq!(
+        NodeType::STRING(String::from("beta")),
+        nodes.find("servers[1].host").unwrap().value
+    );
+    assert_eq!(
+        NodeType::STRING(String::from("hello \"world\"")),
+        nodes.find("greeting").unwrap().value
+    );
+}
+
+#[test]
+fn dotenv_written_for_docker_env_file() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_procon"))
+        .args([
+            "--dry-run",
+            "dotenv",
+            "tests/resources/yaml/placeholders.yaml",
+        ])
+        .output()
+        .expect("Unable to run procon");
+    assert!(output.status.success());
+
+    let content = String::from_utf8(output.stdout).unwrap();
+    assert!(content.contains("\nSERVER_DESCRIPTION=${app.name} on ${server.port}\n"));
+    assert!(content.contains(
+        "\nSPRING_DATASOURCE_URL=jdbc:postgresql://${DB_HOST:localhost}:5432/${app.name}\n"
+    ));
+}
+
+#[test]
+fn dotenv_round_trip() {
+    let input = "tests/resources/yaml/dotenv-values.yaml";
+    let nodes = parse_input_file(&create_args(Delimiter::Equals, input)).unwrap();
+
+    let output = env::temp_dir().join("procon-round-trip.env");
+    let mut write_args = create_args(Delimiter::Equals, input);
+    write_args.target_format = TargetFormat::Dotenv {
+        property_delimiter: None,
+        file: PathBuf::from(input),
+        merge_files: vec![],
+    };
+    write_args.output_filename = Some(output.to_str().unwrap().to_string());
+    to_dotenv(&write_args, &nodes).unwrap();
+
+    let read_back =
+        parse_input_file(&create_args(Delimiter::Equals, output.to_str().unwrap())).unwrap();
+    assert_eq!(nodes.flatten(), read_back.flatten());
+}
+
+#[test]
+fn dotenv_values_read_differently_are_rejected() {
+    let mut args = create_args(Delimiter::Equals, "application.yaml");
+    args.dry_run = true;
+    for content in ["quoted: '\"a\"'\n", "comment: 'a #1'\n", "padded: ' a'\n"] {
+        let documents = YamlFileReader::parse_documents(content).unwrap();
+        assert!(to_dotenv(&args, &documents[0]).is_err(), "{}", content);
+    }
+}
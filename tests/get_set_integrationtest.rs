@@ -0,0 +1,150 @@
+use std::process::{Command, Output};
+
+use procon::node::NodeType;
+use procon::property_file_reader::Delimiter;
+use procon::yaml_file_reader::YamlFileReader;
+
+use crate::common::{assert_node, parse_test_file};
+
+mod common;
+
+#[test]
+fn get_nested_key() {
+    let nodes = parse_test_file(Delimiter::Equals, "tests/resources/yaml/list-of-maps.yaml");
+
+    let node = nodes.find("spec.containers[1].image").unwrap();
+    assert_node(node, "image".to_string(), "envoy".to_string());
+    let node = nodes.find("spec.matrix[1][0]").unwrap();
+    assert_node(node, "0".to_string(), "3".to_string());
+    assert!(nodes.find("spec.containers[9]").is_none());
+    assert!(nodes.find("unknown.key").is_none());
+}
+
+#[test]
+fn get_indexed_key() {
+    let nodes = parse_test_file(
+        Delimiter::Equals,
+        "tests/resources/properties/indexed_arrays.properties",
+    );
+
+    let node = nodes.find("servers[1].host").unwrap();
+    assert_node(node, "host".to_string(), "beta".to_string());
+}
+
+#[test]
+fn set_existing_and_new_keys() {
+    let mut nodes = parse_test_file(
+        Delimiter::Equals,
+        "tests/resources/properties/indexed_arrays.properties",
+    );
+
+    nodes.set("servers[1].host", "gamma").unwrap();
+    nodes.set("servers[2].host", "delta").unwrap();
+    nodes.set("logging.level.root", "warn").unwrap();
+
+    assert_node(
+        nodes.find("servers[1].host").unwrap(),
+        "host".to_string(),
+        "gamma".to_string(),
+    );
+    assert_node(
+        nodes.find("servers[2].host").unwrap(),
+        "host".to_string(),
+        "delta".to_string(),
+    );
+    assert_eq!(
+        NodeType::STRING("warn".to_string()),
+        nodes.find("logging.level.root").unwrap().value
+    );
+}
+
+#[test]
+fn set_index_out_of_range() {
+    let mut nodes = parse_test_file(
+        Delimiter::Equals,
+        "tests/resources/properties/indexed_arrays.properties",
+    );
+
+    let error = nodes.set("servers[3].host", "z").unwrap_err();
+    assert_eq!(
+        "Index 3 of servers[3].host is out of range, the array has 2 elements",
+        error.message
+    );
+    assert!(nodes.set("logging.hosts[1]", "z").is_err());
+    assert!(nodes.find("servers[2]").is_none());
+}
+
+fn procon(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_procon"))
+        .args(args)
+        .output()
+        .expect("Unable to run procon")
+}
+
+#[test]
+fn set_command_keeps_commas() {
+    let output = procon(&[
+        "--dry-run",
+        "set",
+        "spec.containers[0].name=a,b",
+        "tests/resources/yaml/list-of-maps.yaml",
+    ]);
+    assert!(output.status.success());
+
+    let content = String::from_utf8(output.stdout).unwrap();
+    let documents = YamlFileReader::parse_documents(&content).unwrap();
+    assert_eq!(
+        NodeType::STRING("a,b".to_string()),
+        documents[0].find("spec.containers[0].name").unwrap().value
+    );
+}
+
+#[test]
+fn set_command_index_out_of_range() {
+    let output = procon(&[
+        "--dry-run",
+        "set",
+        "spec.containers[3].name=z",
+        "tests/resources/yaml/list-of-maps.yaml",
+    ]);
+    assert_eq!(Some(exitcode::CONFIG), output.status.code());
+    assert!(!String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("name: z"));
+}
+
+#[test]
+fn set_command_keeps_placeholders() {
+    let output = procon(&[
+        "--interpolate",
+        "--dry-run",
+        "set",
+        "app.port=9090",
+        "tests/resources/yaml/placeholders.yaml",
+    ]);
+    assert!(output.status.success());
+
+    let content = String::from_utf8(output.stdout).unwrap();
+    assert!(content.contains("port: 9090"));
+    assert!(content.contains("port: \"${app.port}\""));
+    assert!(content.contains("description: \"${app.name} on ${server.port}\""));
+}
+
+#[test]
+fn get_command_indexed_key() {
+    let output = procon(&[
+        "get",
+        "spec.containers[0]",
+        "tests/resources/yaml/list-of-maps.yaml",
+    ]);
+    assert!(output.status.success());
+    assert_eq!(
+        "spec.containers[0].name=web\nspec.containers[0].image=nginx\nspec.containers[0].ports=80,443\n",
+        String::from_utf8(output.stdout).unwrap()
+    );
+
+    let output = procon(&["get", "spec", "tests/resources/yaml/list-of-maps.yaml"]);
+    let content = String::from_utf8(output.stdout).unwrap();
+    assert!(content.starts_with("spec.containers[0].name=web\n"));
+    assert!(content.contains("spec.matrix[1][0]=3\n"));
+}
@@ -0,0 +1,38 @@
+use procon::node::NodeType;
+use procon::parse_input_file;
+use procon::property_file_reader::Delimiter;
+
+use crate::common::create_args;
+
+mod common;
+
+#[test]
+fn interpolate_yaml_file() {
+    let mut args = create_args(Delimiter::Equals, "tests/resources/yaml/placeholders.yaml");
+    args.interpolate = true;
+    let nodes = parse_input_file(&args).unwrap();
+
+    assert_eq!(
+        NodeType::STRING(String::from("jdbc:postgresql://localhost:5432/demo")),
+        nodes.find("spring.datasource.url").unwrap().value
+    );
+    assert_eq!(
+        NodeType::NUMERIC(String::from("8080")),
+        nodes.find("server.port").unwrap().value
+    );
+    assert_eq!(
+        NodeType::STRING(String::from("demo on 8080")),
+        nodes.find("server.description").unwrap().value
+    );
+}
+
+#[test]
+fn interpolation_is_opt_in() {
+    let args = create_args(Delimiter::Equals, "tests/resources/yaml/placeholders.yaml");
+    let nodes = parse_input_file(&args).unwrap();
+
+    assert_eq!(
+        NodeType::STRING(String::from("${app.port}")),
+        nodes.find("server.port").unwrap().value
+    );
+}
@@ -1,23 +1,20 @@
+use procon::node::NodeType;
+use procon::parse_input_file;
 use procon::property_file_reader::Delimiter;
-use test_helper::parse_test_file;
 
-use crate::test_helper::assert_node;
+use crate::common::{assert_node, create_args, parse_test_file};
 
-mod test_helper;
+mod common;
 
 #[test]
 fn json_file_values_list() {
     let nodes = parse_test_file(Delimiter::Equals, "tests/resources/json/list.json");
 
     let list_node = nodes.get(0).unwrap();
-    assert_node(&list_node, "list".to_string(), "".to_string());
+    assert_node(list_node, "list".to_string(), "".to_string());
 
-    let values = list_node.children.get(0).unwrap();
-    assert_node(
-        &values,
-        "all-types".to_string(),
-        "value1,2,20.4".to_string(),
-    );
+    let values = list_node.children.first().unwrap();
+    assert_node(values, "all-types".to_string(), "value1,2,20.4".to_string());
 }
 #[test]
 fn json_file_values_string() {
@@ -25,7 +22,7 @@ fn json_file_values_string() {
 
     let string_node = nodes.get(0).unwrap();
     assert_node(
-        &string_node,
+        string_node,
         "string".to_string(),
         "This is a string".to_string(),
     );
@@ -36,7 +33,7 @@ fn json_file_values_usize() {
     let nodes = parse_test_file(Delimiter::Equals, "tests/resources/json/usize.json");
 
     let string_node = nodes.get(0).unwrap();
-    assert_node(&string_node, "usize".to_string(), "20".to_string());
+    assert_node(string_node, "usize".to_string(), "20".to_string());
 }
 
 #[test]
@@ -44,7 +41,7 @@ fn json_file_values_float() {
     let nodes = parse_test_file(Delimiter::Equals, "tests/resources/json/float.json");
 
     let string_node = nodes.get(0).unwrap();
-    assert_node(&string_node, "float".to_string(), "20.4".to_string());
+    assert_node(string_node, "float".to_string(), "20.4".to_string());
 }
 
 #[test]
@@ -52,20 +49,20 @@ fn json_file_nodes_nested() {
     let nodes = parse_test_file(Delimiter::Equals, "tests/resources/json/application.json");
 
     let management_node = nodes.get(0).unwrap();
-    assert_node(&management_node, "management".to_string(), "".to_string());
+    assert_node(management_node, "management".to_string(), "".to_string());
 
-    let metrics_node = management_node.children.get(0).unwrap();
-    assert_node(&metrics_node, "metrics".to_string(), "".to_string());
+    let metrics_node = management_node.children.first().unwrap();
+    assert_node(metrics_node, "metrics".to_string(), "".to_string());
     let port_node = management_node.children.get(1).unwrap();
-    assert_node(&port_node, "port".to_string(), "8080".to_string());
+    assert_node(port_node, "port".to_string(), "8080".to_string());
 
-    let enable_node = metrics_node.children.get(0).unwrap();
-    assert_node(&enable_node, "enable".to_string(), "".to_string());
+    let enable_node = metrics_node.children.first().unwrap();
+    assert_node(enable_node, "enable".to_string(), "".to_string());
 
-    let all = enable_node.children.get(0).unwrap();
-    assert_node(&all, "all".to_string(), "false".to_string());
+    let all = enable_node.children.first().unwrap();
+    assert_node(all, "all".to_string(), "false".to_string());
     let http_node = enable_node.children.get(1).unwrap();
-    assert_node(&http_node, "http".to_string(), "true".to_string());
+    assert_node(http_node, "http".to_string(), "true".to_string());
 }
 
 // todo currently root list conversion is not working at all
@@ -74,5 +71,64 @@ fn json_file_value_root_list() {
     let nodes = parse_test_file(Delimiter::Equals, "tests/resources/json/root-list.json");
 
     let list_node = nodes.get(0).unwrap();
-    assert_node(&list_node, "".to_string(), "test,20,20.4,true".to_string());
+    assert_node(list_node, "".to_string(), "test,20,20.4,true".to_string());
+}
+
+#[test]
+fn json_file_list_of_objects() {
+    let nodes = parse_test_file(
+        Delimiter::Equals,
+        "tests/resources/json/list-of-objects.json",
+    );
+
+    let containers_node = nodes.get(0).unwrap();
+    let containers = match &containers_node.value {
+        NodeType::ARRAY(elements) => elements,
+        _ => panic!("Wrong type"),
+    };
+    assert_eq!(2, containers.len());
+
+    let web_node = containers.first().unwrap();
+    assert_node(
+        web_node.children.first().unwrap(),
+        "name".to_string(),
+        "web".to_string(),
+    );
+    assert_node(
+        web_node.children.get(1).unwrap(),
+        "ports".to_string(),
+        "80,443".to_string(),
+    );
+    let sidecar_node = containers.get(1).unwrap();
+    assert_node(
+        sidecar_node.children.first().unwrap(),
+        "name".to_string(),
+        "sidecar".to_string(),
+    );
+}
+
+#[test]
+fn json_file_null() {
+    let nodes = parse_test_file(Delimiter::Equals, "tests/resources/json/null.json");
+
+    let empty_node = nodes.get(0).unwrap();
+    assert_eq!("empty", empty_node.name);
+    assert_eq!(NodeType::NULL, empty_node.value);
+
+    let list_node = nodes.get(1).unwrap();
+    match &list_node.value {
+        NodeType::ARRAY(elements) => assert_eq!(NodeType::NULL, elements[1].value),
+        _ => panic!("Wrong type"),
+    }
+
+    let nested_node = nodes.get(2).unwrap();
+    assert_eq!(NodeType::NULL, nested_node.children[0].value);
+}
+
+#[test]
+fn json_file_not_utf8() {
+    let args = create_args(Delimiter::Equals, "tests/resources/json/latin1.json");
+
+    let error = parse_input_file(&args).unwrap_err();
+    assert_eq!("File is not valid UTF-8", error.message);
 }
@@ -0,0 +1,83 @@
+use std::path::PathBuf;
+
+use procon::args::TargetFormat;
+use procon::nodes::ArrayMerge;
+use procon::parse_input_file;
+use procon::property_file_reader::Delimiter;
+
+use crate::common::{assert_node, create_args, parse_test_file};
+
+mod common;
+
+fn merge_args(array_merge: ArrayMerge) -> procon::args::Args {
+    let mut args = create_args(Delimiter::Equals, "tests/resources/merge/application.yaml");
+    args.target_format = TargetFormat::Json {
+        property_delimiter: Some(Delimiter::Equals),
+        jsonc: false,
+        indent: 1,
+        compact: false,
+        sort_keys: false,
+        ascii: false,
+        flat: false,
+        ndjson: false,
+        file: PathBuf::from("tests/resources/merge/application.yaml"),
+        merge_files: vec![PathBuf::from(
+            "tests/resources/merge/application-prod.properties",
+        )],
+    };
+    args.array_merge = array_merge;
+    args
+}
+
+#[test]
+fn merge_without_merge_files() {
+    let nodes = parse_test_file(Delimiter::Equals, "tests/resources/merge/application.yaml");
+
+    let server = nodes.get(0).unwrap();
+    assert_node(
+        &server.children[0],
+        "host".to_string(),
+        "localhost".to_string(),
+    );
+    assert_node(&server.children[1], "port".to_string(), "8080".to_string());
+}
+
+#[test]
+fn merge_later_file_overrides() {
+    let nodes = parse_input_file(&merge_args(ArrayMerge::Replace)).unwrap();
+
+    assert_eq!(2, nodes.iter().len());
+    let server = nodes.get(0).unwrap();
+    assert_eq!(3, server.children.len());
+    assert_node(&server.children[0], "port".to_string(), "443".to_string());
+    assert_node(
+        &server.children[1],
+        "host".to_string(),
+        "localhost".to_string(),
+    );
+    assert_node(&server.children[2], "ssl".to_string(), "".to_string());
+    assert_node(
+        &server.children[2].children[0],
+        "enabled".to_string(),
+        "true".to_string(),
+    );
+}
+
+#[test]
+fn merge_array_strategies() {
+    let nodes = parse_input_file(&merge_args(ArrayMerge::Replace)).unwrap();
+    let profiles = &nodes.get(1).unwrap().children[0];
+    assert_node(profiles, "profiles".to_string(), "prod,cloud".to_string());
+
+    let nodes = parse_input_file(&merge_args(ArrayMerge::Append)).unwrap();
+    let profiles = &nodes.get(1).unwrap().children[0];
+    assert_node(
+        profiles,
+        "profiles".to_string(),
+        "default,local,prod,cloud".to_string(),
+    );
+
+    let nodes = parse_input_file(&merge_args(ArrayMerge::Index)).unwrap();
+    let profiles = &nodes.get(1).unwrap().children[0];
+    assert_node(profiles, "profiles".to_string(), "prod,cloud".to_string());
+}
@@ -0,0 +1,133 @@
+use std::env;
+use std::path::PathBuf;
+
+use procon::args::TargetFormat;
+use procon::nodes::DelimiterSpacing;
+use procon::nodes_writer::to_properties;
+use procon::parse_input_file;
+use procon::property_file_reader::Delimiter;
+
+use crate::common::create_args;
+
+mod common;
+
+const ESCAPES: &str = "tests/resources/yaml/escapes.yaml";
+
+/// Writes the yaml file as properties and reads the written file back
+fn round_trip(
+    escape_unicode: bool,
+    line_width: Option<usize>,
+    output_delimiter: Delimiter,
+    name: &str,
+) {
+    let args = create_args(Delimiter::Equals, ESCAPES);
+    let nodes = parse_input_file(&args).unwrap();
+
+    let output = env::temp_dir().join(name);
+    let mut write_args = create_args(Delimiter::Equals, ESCAPES);
+    write_args.target_format = TargetFormat::Properties {
+        property_delimiter: None,
+        indexed_arrays: false,
+        escape_unicode,
+        line_width,
+        output_delimiter: output_delimiter.clone(),
+        delimiter_spacing: DelimiterSpacing::Both,
+        sort_keys: false,
+        separate_groups: false,
+        header: None,
+        file: PathBuf::from(ESCAPES),
+        merge_files: vec![],
+    };
+    write_args.output_filename = Some(output.to_str().unwrap().to_string());
+    to_properties(&write_args, &nodes).unwrap();
+
+    let read_back = parse_input_file(&create_args(output_delimiter, output.to_str().unwrap()));
+    assert_eq!(nodes.flatten(), read_back.unwrap().flatten());
+    if escape_unicode {
+        let content = std::fs::read_to_string(&output).unwrap();
+        assert!(content.is_ascii());
+    }
+}
+
+#[test]
+fn properties_round_trip() {
+    round_trip(
+        false,
+        None,
+        Delimiter::Equals,
+        "procon-round-trip.properties",
+    );
+}
+
+#[test]
+fn properties_round_trip_escaped_unicode() {
+    round_trip(
+        true,
+        None,
+        Delimiter::Equals,
+        "procon-round-trip-unicode.properties",
+    );
+}
+
+#[test]
+fn properties_round_trip_wrapped() {
+    round_trip(
+        false,
+        Some(40),
+        Delimiter::Colon,
+        "procon-round-trip-wrapped.properties",
+    );
+}
+
+#[test]
+fn properties_round_trip_whitespace_delimiter() {
+    round_trip(
+        false,
+        None,
+        Delimiter::Whitespace,
+        "procon-round-trip-whitespace.properties",
+    );
+}
+
+fn to_json(nodes: &procon::nodes::Nodes) -> String {
+    let mut json_data = json::JsonValue::new_object();
+    for node in nodes.iter() {
+        json_data[node.name.clone()] = node.into();
+    }
+    json_data.dump()
+}
+
+#[test]
+fn properties_json_round_trip() {
+    let input = "tests/resources/json/comma-elements.json";
+    let nodes = parse_input_file(&create_args(Delimiter::Equals, input)).unwrap();
+
+    let output = env::temp_dir().join("procon-comma-elements.properties");
+    let mut write_args = create_args(Delimiter::Equals, input);
+    write_args.target_format = TargetFormat::Properties {
+        property_delimiter: None,
+        indexed_arrays: false,
+        escape_unicode: false,
+        line_width: None,
+        output_delimiter: Delimiter::Equals,
+        delimiter_spacing: DelimiterSpacing::None,
+        sort_keys: false,
+        separate_groups: false,
+        header: None,
+        file: PathBuf::from(input),
+        merge_files: vec![],
+    };
+    write_args.output_filename = Some(output.to_str().unwrap().to_string());
+    to_properties(&write_args, &nodes).unwrap();
+
+    assert_eq!(
+        "list[0]=a,b\nlist[1]=c\nmatrix[0][0]=1\nmatrix[0][1]=2\nmatrix[1][0]=3\nplain=x,y\n",
+        std::fs::read_to_string(&output).unwrap()
+    );
+    let read_back =
+        parse_input_file(&create_args(Delimiter::Equals, output.to_str().unwrap())).unwrap();
+    assert_eq!(
+        r#"{"list":["a,b","c"],"matrix":[[1,2],[3]],"plain":["x","y"]}"#,
+        to_json(&read_back)
+    );
+}
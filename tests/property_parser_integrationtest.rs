@@ -1,9 +1,13 @@
+use std::path::PathBuf;
+
+use procon::args::TargetFormat;
+use procon::node::NodeType;
+use procon::parse_input_file;
 use procon::property_file_reader::Delimiter;
-use test_helper::parse_test_file;
 
-use crate::test_helper::assert_node;
+use crate::common::{assert_node, create_args, parse_test_file};
 
-mod test_helper;
+mod common;
 
 #[test]
 fn property_file_values_list() {
@@ -13,14 +17,10 @@ fn property_file_values_list() {
     );
 
     let list_node = nodes.get(0).unwrap();
-    assert_node(&list_node, "list".to_string(), "".to_string());
+    assert_node(list_node, "list".to_string(), "".to_string());
 
-    let values = list_node.children.get(0).unwrap();
-    assert_node(
-        &values,
-        "all-types".to_string(),
-        "value1,2,20.4".to_string(),
-    );
+    let values = list_node.children.first().unwrap();
+    assert_node(values, "all-types".to_string(), "value1,2,20.4".to_string());
 }
 
 #[test]
@@ -32,7 +32,7 @@ fn property_file_values_string() {
 
     let string_node = nodes.get(0).unwrap();
     assert_node(
-        &string_node,
+        string_node,
         "string".to_string(),
         "This is a string".to_string(),
     );
@@ -46,7 +46,7 @@ fn property_file_values_usize() {
     );
 
     let string_node = nodes.get(0).unwrap();
-    assert_node(&string_node, "usize".to_string(), "20".to_string());
+    assert_node(string_node, "usize".to_string(), "20".to_string());
 }
 
 #[test]
@@ -57,7 +57,7 @@ fn property_file_values_float() {
     );
 
     let string_node = nodes.get(0).unwrap();
-    assert_node(&string_node, "float".to_string(), "20.4".to_string());
+    assert_node(string_node, "float".to_string(), "20.4".to_string());
 }
 
 #[test]
@@ -69,15 +69,15 @@ fn property_file_nodes_nested() {
 
     // reader node
     let reader_node = nodes.get(0).unwrap();
-    assert_node(&reader_node, "reader".to_string(), "".to_string());
+    assert_node(reader_node, "reader".to_string(), "".to_string());
 
-    let datasource_node = reader_node.children.get(0).unwrap();
-    assert_node(&datasource_node, "datasource".to_string(), "".to_string());
+    let datasource_node = reader_node.children.first().unwrap();
+    assert_node(datasource_node, "datasource".to_string(), "".to_string());
 
-    let host_node = datasource_node.children.get(0).unwrap();
-    assert_node(&host_node, "host".to_string(), "localhost".to_string());
+    let host_node = datasource_node.children.first().unwrap();
+    assert_node(host_node, "host".to_string(), "localhost".to_string());
     let username_node = datasource_node.children.get(1).unwrap();
-    assert_node(&username_node, "username".to_string(), "user".to_string());
+    assert_node(username_node, "username".to_string(), "user".to_string());
 }
 
 #[test]
@@ -89,17 +89,17 @@ fn property_file_nodes_multiple() {
 
     // reader node
     let reader_node = nodes.get(0).unwrap();
-    assert_node(&reader_node, "reader".to_string(), "".to_string());
-    let host_node = reader_node.children.get(0).unwrap();
-    assert_node(&host_node, "host".to_string(), "localhost2".to_string());
+    assert_node(reader_node, "reader".to_string(), "".to_string());
+    let host_node = reader_node.children.first().unwrap();
+    assert_node(host_node, "host".to_string(), "localhost2".to_string());
     let username_node = reader_node.children.get(1).unwrap();
-    assert_node(&username_node, "username".to_string(), "user".to_string());
+    assert_node(username_node, "username".to_string(), "user".to_string());
 
     // writer node
     let writer_node = nodes.get(1).unwrap();
-    assert_node(&writer_node, "writer".to_string(), "".to_string());
-    let host_node = writer_node.children.get(0).unwrap();
-    assert_node(&host_node, "host".to_string(), "localhost".to_string());
+    assert_node(writer_node, "writer".to_string(), "".to_string());
+    let host_node = writer_node.children.first().unwrap();
+    assert_node(host_node, "host".to_string(), "localhost".to_string());
 }
 
 #[test]
@@ -110,15 +110,15 @@ fn property_file_delimiter_colon() {
     );
 
     let datasource_node = nodes.get(0).unwrap();
-    assert_node(&datasource_node, "datasource".to_string(), "".to_string());
-    let jdbc_url_node = datasource_node.children.get(0).unwrap();
+    assert_node(datasource_node, "datasource".to_string(), "".to_string());
+    let jdbc_url_node = datasource_node.children.first().unwrap();
     assert_node(
-        &jdbc_url_node,
+        jdbc_url_node,
         "jdbc-url".to_string(),
         "jdbc:postgresql://localhost:5432/user".to_string(),
     );
     let username_node = datasource_node.children.get(1).unwrap();
-    assert_node(&username_node, "username".to_string(), "user".to_string());
+    assert_node(username_node, "username".to_string(), "user".to_string());
 }
 
 #[test]
@@ -129,7 +129,7 @@ fn property_file_edge_case_empty_value() {
     );
 
     let empty_node = nodes.get(0).unwrap();
-    assert_node(&empty_node, "empty".to_string(), "".to_string());
+    assert_node(empty_node, "empty".to_string(), "".to_string());
 }
 
 #[test]
@@ -140,7 +140,7 @@ fn property_file_edge_case_whitespace_ignored() {
     );
 
     let level1_node = nodes.get(0).unwrap();
-    assert_node(&level1_node, "hello".to_string(), "hello".to_string());
+    assert_node(level1_node, "hello".to_string(), "hello".to_string());
 }
 
 #[test]
@@ -152,7 +152,7 @@ fn property_file_edge_case_duplicated_keys() {
 
     let level1_node = nodes.get(0).unwrap();
     assert_node(
-        &level1_node,
+        level1_node,
         "duplicateKey".to_string(),
         "second".to_string(),
     );
@@ -167,9 +167,9 @@ fn property_file_edge_case_escape_even() {
 
     let even_key = nodes.get(0).unwrap();
     assert_node(
-        &even_key,
+        even_key,
         "evenKey".to_string(),
-        "This is on one line\\\\".to_string(),
+        "This is on one line\\".to_string(),
     );
 }
 
@@ -182,7 +182,7 @@ fn property_file_edge_case_multiline() {
 
     let multiline = nodes.get(0).unwrap();
     assert_node(
-        &multiline,
+        multiline,
         "multiline".to_string(),
         "This line continues".to_string(),
     );
@@ -197,9 +197,9 @@ fn property_file_edge_case_escape_odd() {
 
     let odd_key = nodes.get(0).unwrap();
     assert_node(
-        &odd_key,
+        odd_key,
         "oddKey".to_string(),
-        "This is line one and\\\\# This is line two".to_string(),
+        "This is line one and\\# This is line two".to_string(),
     );
 }
 
@@ -211,11 +211,7 @@ fn property_file_edge_case_escape_path() {
     );
 
     let path = nodes.get(0).unwrap();
-    assert_node(
-        &path,
-        "path".to_string(),
-        "c:\\\\wiki\\\\templates".to_string(),
-    );
+    assert_node(path, "path".to_string(), "c:\\wiki\\templates".to_string());
 }
 
 #[test]
@@ -227,9 +223,9 @@ fn property_file_edge_case_escape_values() {
 
     let value_with_escapes = nodes.get(0).unwrap();
     assert_node(
-        &value_with_escapes,
+        value_with_escapes,
         "valueWithEscapes".to_string(),
-        "This is a newline\\n and a carriage return\\r and a tab\\t.".to_string(),
+        "This is a newline\n and a carriage return\r and a tab\t.".to_string(),
     );
 }
 
@@ -242,8 +238,194 @@ fn property_file_edge_case_escape() {
 
     let welcome = nodes.get(0).unwrap();
     assert_node(
-        &welcome,
+        welcome,
         "welcome".to_string(),
         "Welcome to Wikipedia!".to_string(),
     );
 }
+
+#[test]
+fn property_file_keeps_file_order() {
+    let args = create_args(
+        Delimiter::Equals,
+        "tests/resources/properties/nodes_order.properties",
+    );
+    let nodes = parse_input_file(&args).unwrap();
+
+    let names: Vec<&str> = nodes.iter().map(|node| node.name.as_str()).collect();
+    assert_eq!(vec!["server", "app", "logging"], names);
+
+    let server_node = nodes.get(0).unwrap();
+    assert_node(
+        server_node.children.first().unwrap(),
+        "port".to_string(),
+        "8080".to_string(),
+    );
+    assert_node(
+        server_node.children.get(1).unwrap(),
+        "address".to_string(),
+        "localhost".to_string(),
+    );
+}
+
+#[test]
+fn property_file_indexed_arrays() {
+    let nodes = parse_test_file(
+        Delimiter::Equals,
+        "tests/resources/properties/indexed_arrays.properties",
+    );
+
+    let matrix_node = nodes.get(0).unwrap();
+    assert_eq!("matrix", matrix_node.name);
+    match &matrix_node.value {
+        NodeType::ARRAY(rows) => assert_eq!("1,2", rows[0].value.to_string()),
+        _ => panic!("Wrong type"),
+    }
+
+    let profiles_node = nodes.get(1).unwrap();
+    assert_node(
+        profiles_node,
+        "profiles".to_string(),
+        "dev,prod".to_string(),
+    );
+
+    // elements are ordered by their index
+    let servers_node = nodes.get(2).unwrap();
+    let servers = match &servers_node.value {
+        NodeType::ARRAY(elements) => elements,
+        _ => panic!("Wrong type"),
+    };
+    assert_eq!(2, servers.len());
+    assert_node(
+        servers[0].children.first().unwrap(),
+        "host".to_string(),
+        "alpha".to_string(),
+    );
+    assert_node(
+        servers[0].children.get(1).unwrap(),
+        "port".to_string(),
+        "8001".to_string(),
+    );
+    assert_node(
+        servers[1].children.first().unwrap(),
+        "host".to_string(),
+        "beta".to_string(),
+    );
+
+    // indexed elements are single values
+    let tags_node = nodes.get(3).unwrap();
+    match &tags_node.value {
+        NodeType::ARRAY(tags) => {
+            assert_eq!(2, tags.len());
+            assert_eq!(NodeType::STRING("a,b".to_string()), tags[0].value);
+        }
+        _ => panic!("Wrong type"),
+    }
+
+    // invalid indices are kept as part of the key
+    let weird_node = nodes.get(4).unwrap();
+    assert_node(weird_node, "weird[abc]".to_string(), "literal".to_string());
+}
+
+#[test]
+fn property_file_numbers() {
+    let nodes = parse_test_file(
+        Delimiter::Equals,
+        "tests/resources/properties/numbers.properties",
+    );
+
+    // sorted by key: bond, exp, id, port, version
+    assert_eq!(
+        NodeType::STRING("007".to_string()),
+        nodes.get(0).unwrap().value
+    );
+    assert_eq!(
+        NodeType::STRING("1e5".to_string()),
+        nodes.get(1).unwrap().value
+    );
+    assert_eq!(
+        NodeType::NUMERIC("12345678901234567890123".to_string()),
+        nodes.get(2).unwrap().value
+    );
+    assert_eq!(
+        NodeType::NUMERIC("8080".to_string()),
+        nodes.get(3).unwrap().value
+    );
+    assert_eq!(
+        NodeType::NUMERIC("1.10".to_string()),
+        nodes.get(4).unwrap().value
+    );
+}
+
+#[test]
+fn property_file_numbers_strict() {
+    let mut args = create_args(
+        Delimiter::Equals,
+        "tests/resources/properties/numbers.properties",
+    );
+    args.strict_numbers = true;
+    args.string_keys = vec!["port".to_string()];
+    let nodes = parse_input_file(&args).unwrap();
+
+    let values: Vec<&NodeType> = nodes.iter().map(|node| &node.value).collect();
+    assert!(values
+        .iter()
+        .all(|value| matches!(value, NodeType::STRING(_))));
+}
+
+#[test]
+fn property_file_edge_case_java_spec() {
+    let args = create_args(
+        Delimiter::Equals,
+        "tests/resources/properties/edge_case_java_spec.properties",
+    );
+    let nodes = parse_input_file(&args).unwrap();
+
+    // the file is not valid UTF-8 and read as ISO-8859-1
+    assert_eq!(
+        "Grüße aus München",
+        nodes.find("greeting").unwrap().value.to_string()
+    );
+    assert_eq!(
+        "value",
+        nodes.find("escaped=key:name").unwrap().value.to_string()
+    );
+    assert_eq!("äöü €", nodes.find("unicode").unwrap().value.to_string());
+    assert_eq!(
+        "alpha, beta, gamma",
+        nodes.find("list").unwrap().value.to_string()
+    );
+}
+
+#[test]
+fn property_file_mixed_delimiters() {
+    let filename = "tests/resources/properties/mixed_delimiters.properties";
+    let mut args = create_args(Delimiter::Equals, filename);
+    args.target_format = TargetFormat::Json {
+        property_delimiter: None,
+        jsonc: false,
+        indent: 1,
+        compact: false,
+        sort_keys: false,
+        ascii: false,
+        flat: false,
+        ndjson: false,
+        file: PathBuf::from(filename),
+        merge_files: vec![],
+    };
+    let nodes = parse_input_file(&args).unwrap();
+
+    let values = nodes.flatten();
+    let expected = [
+        ("datasource.url", "jdbc:postgresql://localhost:5432/user"),
+        ("datasource.username", "user"),
+        ("datasource.password", "secret"),
+        ("datasource.pool-size", "10"),
+        ("datasource.timeout", "30"),
+        ("datasource.driver", "org.postgresql.Driver"),
+        ("datasource.schema", "public"),
+    ];
+    for (key, value) in expected {
+        assert_eq!(Some(&value.to_string()), values.get(key), "{}", key);
+    }
+}
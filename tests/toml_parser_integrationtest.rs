@@ -0,0 +1,89 @@
+use procon::node::NodeType;
+use procon::nodes_writer::to_json;
+use procon::property_file_reader::Delimiter;
+
+use crate::common::{assert_node, create_args, parse_test_file};
+
+mod common;
+
+#[test]
+fn toml_file_values_string() {
+    let nodes = parse_test_file(Delimiter::Equals, "tests/resources/toml/string.toml");
+
+    let string_node = nodes.get(0).unwrap();
+    assert_node(
+        string_node,
+        "string".to_string(),
+        "This is a string".to_string(),
+    );
+}
+
+#[test]
+fn toml_file_values_list() {
+    let nodes = parse_test_file(Delimiter::Equals, "tests/resources/toml/list.toml");
+
+    let list_node = nodes.get(0).unwrap();
+    assert_node(list_node, "list".to_string(), "".to_string());
+
+    let values = list_node.children.first().unwrap();
+    assert_node(values, "all-types".to_string(), "value1,2,20.4".to_string());
+}
+
+#[test]
+fn toml_file_nodes_nested() {
+    let nodes = parse_test_file(Delimiter::Equals, "tests/resources/toml/application.toml");
+
+    let management_node = nodes.get(0).unwrap();
+    assert_node(management_node, "management".to_string(), "".to_string());
+
+    let metrics_node = management_node.children.first().unwrap();
+    assert_node(metrics_node, "metrics".to_string(), "".to_string());
+    let port_node = management_node.children.get(1).unwrap();
+    assert_node(port_node, "port".to_string(), "8080".to_string());
+
+    let enable_node = metrics_node.children.first().unwrap();
+    assert_node(enable_node, "enable".to_string(), "".to_string());
+
+    let all = enable_node.children.first().unwrap();
+    assert_node(all, "all".to_string(), "false".to_string());
+    let http_node = enable_node.children.get(1).unwrap();
+    assert_node(http_node, "http".to_string(), "true".to_string());
+}
+
+#[test]
+fn toml_file_array_of_tables() {
+    let nodes = parse_test_file(
+        Delimiter::Equals,
+        "tests/resources/toml/array-of-tables.toml",
+    );
+
+    let servers_node = nodes.get(0).unwrap();
+    let elements = match &servers_node.value {
+        NodeType::ARRAY(elements) => elements,
+        _ => panic!("Wrong type"),
+    };
+
+    let first_node = elements.first().unwrap();
+    let host_node = first_node.children.first().unwrap();
+    assert_node(host_node, "host".to_string(), "alpha".to_string());
+    let port_node = first_node.children.get(1).unwrap();
+    assert_node(port_node, "port".to_string(), "8001".to_string());
+
+    let second_node = elements.get(1).unwrap();
+    let host_node = second_node.children.first().unwrap();
+    assert_node(host_node, "host".to_string(), "beta".to_string());
+}
+
+#[test]
+fn toml_nan_and_infinity_not_written_as_json() {
+    let filename = "tests/resources/toml/special-floats.toml";
+    let nodes = parse_test_file(Delimiter::Equals, filename);
+    let mut args = create_args(Delimiter::Equals, filename);
+    args.dry_run = true;
+
+    let error = to_json(&args, &nodes).unwrap_err();
+    assert_eq!(
+        "Value -inf of floor is not supported in json format",
+        error.message
+    );
+}
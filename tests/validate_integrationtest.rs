@@ -0,0 +1,53 @@
+use std::fs;
+
+use procon::nodes_validator::{validate, Violation};
+use procon::property_file_reader::{Delimiter, PropertyFileReader};
+use procon::yaml_file_reader::YamlFileReader;
+
+use crate::common::{create_args, parse_test_file};
+
+mod common;
+
+const SCHEMA: &str = "tests/resources/schema/service.schema.json";
+
+fn violation<'a>(violations: &'a [Violation], key: &str) -> &'a Violation {
+    violations
+        .iter()
+        .find(|violation| violation.key == key)
+        .unwrap()
+}
+
+#[test]
+fn validate_yaml_file() {
+    let filename = "tests/resources/schema/invalid.yaml";
+    let nodes = parse_test_file(Delimiter::Equals, filename);
+    let line_numbers = YamlFileReader::line_numbers(&fs::read_to_string(filename).unwrap());
+
+    let violations = validate(&nodes, &fs::read_to_string(SCHEMA).unwrap(), &line_numbers).unwrap();
+    assert_eq!(3, violations.len());
+    assert_eq!(Some(2), violation(&violations, "server.port").line_number);
+    assert_eq!(Some(1), violation(&violations, "server").line_number);
+    assert_eq!(
+        Some(7),
+        violation(&violations, "servers[1].port").line_number
+    );
+}
+
+#[test]
+fn validate_properties_file() {
+    let filename = "tests/resources/schema/invalid.properties";
+    let nodes = parse_test_file(Delimiter::Equals, filename);
+    let args = create_args(Delimiter::Equals, filename);
+    let line_numbers =
+        PropertyFileReader::line_numbers(&args, &fs::read_to_string(filename).unwrap());
+
+    let violations = validate(&nodes, &fs::read_to_string(SCHEMA).unwrap(), &line_numbers).unwrap();
+    assert_eq!(3, violations.len());
+    assert_eq!(Some(2), violation(&violations, "server.port").line_number);
+    // the missing host is reported at the first key of the server
+    assert_eq!(Some(2), violation(&violations, "server").line_number);
+    assert_eq!(
+        Some(6),
+        violation(&violations, "servers[1].port").line_number
+    );
+}
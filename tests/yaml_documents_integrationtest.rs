@@ -0,0 +1,91 @@
+use procon::node::NodeType;
+use procon::parse_input_file;
+use procon::property_file_reader::Delimiter;
+use procon::yaml_file_reader::YamlFileReader;
+
+use crate::common::create_args;
+
+mod common;
+
+const PROFILES: &str = "tests/resources/yaml/profiles.yaml";
+
+fn value(args: &procon::args::Args, key: &str) -> Option<NodeType> {
+    let nodes = parse_input_file(args).unwrap();
+    nodes.find(key).map(|node| node.value.clone())
+}
+
+#[test]
+fn yaml_documents_merged_in_order() {
+    let args = create_args(Delimiter::Equals, PROFILES);
+
+    assert_eq!(
+        Some(NodeType::NUMERIC("8080".to_string())),
+        value(&args, "server.port")
+    );
+    assert_eq!(
+        Some(NodeType::STRING("localhost".to_string())),
+        value(&args, "server.host")
+    );
+    assert_eq!(None, value(&args, "logging.level"));
+}
+
+#[test]
+fn yaml_documents_without_profile_merged_in_order() {
+    let content = "a: 1\nb: 1\n---\nb: 2\n---\nspring:\n  profiles: dev\nb: 3\n";
+    let args = create_args(Delimiter::Equals, PROFILES);
+    let nodes = YamlFileReader::parse(&args, content).unwrap();
+
+    assert_eq!(
+        Some(NodeType::NUMERIC("1".to_string())),
+        nodes.find("a").map(|node| node.value.clone())
+    );
+    assert_eq!(
+        Some(NodeType::NUMERIC("2".to_string())),
+        nodes.find("b").map(|node| node.value.clone())
+    );
+    assert!(nodes.find("spring.profiles").is_none());
+}
+
+#[test]
+fn yaml_document_by_index() {
+    let mut args = create_args(Delimiter::Equals, PROFILES);
+    args.document = Some(0);
+    assert_eq!(
+        Some(NodeType::NUMERIC("8080".to_string())),
+        value(&args, "server.port")
+    );
+    assert_eq!(None, value(&args, "logging.level"));
+
+    args.document = Some(4);
+    assert!(parse_input_file(&args).is_err());
+}
+
+#[test]
+fn yaml_documents_by_profile() {
+    let mut args = create_args(Delimiter::Equals, PROFILES);
+    args.profile = Some("prod".to_string());
+    assert_eq!(
+        Some(NodeType::NUMERIC("443".to_string())),
+        value(&args, "server.port")
+    );
+    assert_eq!(
+        Some(NodeType::STRING("localhost".to_string())),
+        value(&args, "server.host")
+    );
+    // excluded by !prod
+    assert_eq!(None, value(&args, "logging.level"));
+
+    args.profile = Some("test".to_string());
+    assert_eq!(
+        Some(NodeType::NUMERIC("8080".to_string())),
+        value(&args, "server.port")
+    );
+    assert_eq!(
+        Some(NodeType::STRING("dev.local".to_string())),
+        value(&args, "server.host")
+    );
+    assert_eq!(
+        Some(NodeType::STRING("debug".to_string())),
+        value(&args, "logging.level")
+    );
+}
@@ -1,8 +1,10 @@
+use procon::node::NodeType;
+use procon::parse_input_file;
 use procon::property_file_reader::Delimiter;
 
-use crate::test_helper::{assert_node, parse_test_file};
+use crate::common::{assert_node, create_args, parse_test_file};
 
-mod test_helper;
+mod common;
 
 #[test]
 fn yaml_file_string() {
@@ -11,21 +13,21 @@ fn yaml_file_string() {
 
     let string_node = nodes.get(0).unwrap();
     assert_node(
-        &string_node,
+        string_node,
         "string1".to_string(),
         "Sample String 1".to_string(),
     );
 
     let string_node = nodes.get(1).unwrap();
     assert_node(
-        &string_node,
+        string_node,
         "string2".to_string(),
         "Sample String 2".to_string(),
     );
 
     let string_node = nodes.get(2).unwrap();
     assert_node(
-        &string_node,
+        string_node,
         "string3".to_string(),
         "Sample String 3".to_string(),
     );
@@ -37,7 +39,7 @@ fn yaml_file_list() {
     println!("{:?}", nodes);
 
     let list_node = nodes.get(0).unwrap();
-    assert_node(&list_node, "list".to_string(), "A,B,C".to_string());
+    assert_node(list_node, "list".to_string(), "A,B,C".to_string());
 }
 
 #[test]
@@ -46,7 +48,7 @@ fn yaml_file_root_list() {
     println!("{:?}", nodes);
 
     let list_node = nodes.get(0).unwrap();
-    assert_node(&list_node, "".to_string(), "A,B,C".to_string());
+    assert_node(list_node, "".to_string(), "A,B,C".to_string());
 }
 
 #[test]
@@ -55,9 +57,9 @@ fn yaml_file_types() {
     println!("{:?}", nodes);
 
     let float_node = nodes.get(0).unwrap();
-    assert_node(&float_node, "a".to_string(), "123.0".to_string());
+    assert_node(float_node, "a".to_string(), "123.0".to_string());
     let string_node = nodes.get(1).unwrap();
-    assert_node(&string_node, "b".to_string(), "123".to_string());
+    assert_node(string_node, "b".to_string(), "123".to_string());
 }
 
 #[test]
@@ -65,7 +67,7 @@ fn yaml_file_comment() {
     let nodes = parse_test_file(Delimiter::Equals, "tests/resources/yaml/comment.yaml");
 
     let float_node = nodes.get(0).unwrap();
-    assert_node(&float_node, "key".to_string(), "value".to_string());
+    assert_node(float_node, "key".to_string(), "value".to_string());
 }
 
 #[test]
@@ -74,24 +76,107 @@ fn yaml_file_example() {
     println!("{:?}", nodes);
 
     let address_node = nodes.get(0).unwrap();
-    assert_node(&address_node, "address".to_string(), "".to_string());
-    let list_node = address_node.children.get(0).unwrap();
-    assert_node(&list_node, "list".to_string(), "A,B,C".to_string());
+    assert_node(address_node, "address".to_string(), "".to_string());
+    let list_node = address_node.children.first().unwrap();
+    assert_node(list_node, "list".to_string(), "A,B,C".to_string());
     let street_node = address_node.children.get(1).unwrap();
     assert_node(
-        &street_node,
+        street_node,
         "street".to_string(),
         "123 Tornado Alley\nSuite 16            \ncity:   East Centerville\nstate:  KS\n"
             .to_string(),
     );
 
     let contact_node = nodes.get(1).unwrap();
-    assert_node(&contact_node, "contact".to_string(), "".to_string());
-    let home_node = contact_node.children.get(0).unwrap();
-    assert_node(&home_node, "home".to_string(), "1012355532".to_string());
+    assert_node(contact_node, "contact".to_string(), "".to_string());
+    let home_node = contact_node.children.first().unwrap();
+    assert_node(home_node, "home".to_string(), "1012355532".to_string());
     let office_node = contact_node.children.get(1).unwrap();
-    assert_node(&office_node, "office".to_string(), "5002586256".to_string());
+    assert_node(office_node, "office".to_string(), "5002586256".to_string());
 
     let name_node = nodes.get(2).unwrap();
-    assert_node(&name_node, "name".to_string(), "John Smith".to_string());
+    assert_node(name_node, "name".to_string(), "John Smith".to_string());
+}
+
+#[test]
+fn yaml_file_list_of_maps() {
+    let nodes = parse_test_file(Delimiter::Equals, "tests/resources/yaml/list-of-maps.yaml");
+
+    let spec_node = nodes.get(0).unwrap();
+    let containers_node = spec_node.children.first().unwrap();
+    assert_eq!("containers", containers_node.name);
+    let containers = match &containers_node.value {
+        NodeType::ARRAY(elements) => elements,
+        _ => panic!("Wrong type"),
+    };
+    assert_eq!(2, containers.len());
+
+    let web_node = containers.first().unwrap();
+    assert_node(
+        web_node.children.first().unwrap(),
+        "image".to_string(),
+        "nginx".to_string(),
+    );
+    assert_node(
+        web_node.children.get(2).unwrap(),
+        "ports".to_string(),
+        "80,443".to_string(),
+    );
+
+    let matrix_node = spec_node.children.get(1).unwrap();
+    match &matrix_node.value {
+        NodeType::ARRAY(rows) => {
+            assert_eq!("1,2", rows[0].value.to_string());
+            assert_eq!("3,4", rows[1].value.to_string());
+        }
+        _ => panic!("Wrong type"),
+    }
+}
+
+#[test]
+fn yaml_file_null() {
+    let nodes = parse_test_file(Delimiter::Equals, "tests/resources/yaml/null.yaml");
+
+    let empty_node = nodes.get(0).unwrap();
+    assert_eq!("empty", empty_node.name);
+    assert_eq!(NodeType::NULL, empty_node.value);
+
+    let nested_node = nodes.get(1).unwrap();
+    assert_eq!(NodeType::NULL, nested_node.children[0].value);
+}
+
+#[test]
+fn yaml_file_anchors_and_merge_keys() {
+    let nodes = parse_test_file(Delimiter::Equals, "tests/resources/yaml/anchors.yaml");
+
+    let value = |key: &str| nodes.find(key).unwrap().value.to_string();
+    assert_eq!("postgres", value("development.adapter"));
+    assert_eq!("dev_db", value("development.database"));
+    assert_eq!("db.example.com", value("production.host"));
+    assert_eq!("20", value("production.pool"));
+    assert_eq!("info", value("production.level"));
+    assert_eq!("1", value("chained.top.a"));
+    assert_eq!("2", value("chained.top.b"));
+    assert_eq!("alpha,beta", value("mirror"));
+    assert!(nodes.find("development.<<").is_none());
+}
+
+#[test]
+fn yaml_file_numbers() {
+    let args = create_args(Delimiter::Equals, "tests/resources/yaml/numbers.yaml");
+    let nodes = parse_input_file(&args).unwrap();
+
+    let number = |key: &str| nodes.find(key).unwrap().value.clone();
+    assert_eq!(NodeType::NUMERIC("1.10".to_string()), number("version"));
+    assert_eq!(NodeType::NUMERIC("1.50".to_string()), number("ratio"));
+    assert_eq!(NodeType::NUMERIC("1e5".to_string()), number("exp"));
+    assert_eq!(
+        NodeType::NUMERIC("12345678901234567890".to_string()),
+        number("id")
+    );
+    assert_eq!(NodeType::NUMERIC("8080".to_string()), number("port"));
+    assert_eq!(NodeType::NUMERIC("15".to_string()), number("octal"));
+    assert_eq!(NodeType::STRING("1.10".to_string()), number("quoted"));
+    assert_eq!(NodeType::NUMERIC("1.10".to_string()), number("versions[0]"));
+    assert_eq!(NodeType::NUMERIC("2.0".to_string()), number("versions[1]"));
 }
@@ -0,0 +1,68 @@
+use procon::nodes::TagPolicy;
+use procon::nodes_writer::{to_json, to_properties, to_yaml};
+use procon::parse_input_file;
+use procon::property_file_reader::Delimiter;
+use procon::yaml_file_reader::YamlFileReader;
+
+use crate::common::create_args;
+
+mod common;
+
+const TAGGED: &str = "tests/resources/yaml/tagged.yaml";
+
+#[test]
+fn yaml_tags_read() {
+    let args = create_args(Delimiter::Equals, TAGGED);
+    let nodes = parse_input_file(&args).unwrap();
+
+    let name = nodes.find("bucket.name").unwrap();
+    assert_eq!(Some("!Ref".to_string()), name.tag);
+    assert_eq!("BucketName", name.value.to_string());
+    let arn = nodes.find("bucket.arn").unwrap();
+    assert_eq!(Some("!GetAtt".to_string()), arn.tag);
+    assert_eq!("Bucket,Arn", arn.value.to_string());
+    assert_eq!(
+        Some("!vault".to_string()),
+        nodes.find("password").unwrap().tag
+    );
+    assert_eq!(None, nodes.find("region").unwrap().tag);
+    let logo = nodes.find("logo").unwrap();
+    assert_eq!(Some("!!binary".to_string()), logo.tag);
+    assert_eq!("aGVsbG8=", logo.value.to_string());
+}
+
+#[test]
+fn yaml_tags_written_by_all_writers() {
+    let mut args = create_args(Delimiter::Equals, TAGGED);
+    args.dry_run = true;
+    let nodes = parse_input_file(&args).unwrap();
+
+    assert!(to_yaml(&args, &nodes).is_ok());
+    assert!(to_properties(&args, &nodes).is_ok());
+    args.tagged_values = TagPolicy::Stringify;
+    assert!(to_json(&args, &nodes).is_ok());
+}
+
+#[test]
+fn yaml_tags_error_policy() {
+    let mut args = create_args(Delimiter::Equals, TAGGED);
+    args.dry_run = true;
+    args.tagged_values = TagPolicy::Error;
+    let nodes = parse_input_file(&args).unwrap();
+
+    assert!(to_yaml(&args, &nodes).is_ok());
+    let error = to_json(&args, &nodes).unwrap_err();
+    assert_eq!("Tagged value !Ref in bucket.name", error.message);
+}
+
+#[test]
+fn yaml_core_tags_error_policy() {
+    let mut args = create_args(Delimiter::Equals, TAGGED);
+    args.dry_run = true;
+    args.tagged_values = TagPolicy::Error;
+    let documents = YamlFileReader::parse_documents("logo: !!binary aGVsbG8=\n").unwrap();
+    let nodes = documents.first().unwrap();
+
+    let error = to_properties(&args, nodes).unwrap_err();
+    assert_eq!("Tagged value !!binary in logo", error.message);
+}